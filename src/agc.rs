@@ -0,0 +1,139 @@
+// Automatic LED current control for the MAX30102 family.
+//
+// The DC level of a PPG channel depends on skin tone, tissue thickness and contact pressure
+// as much as on the LED current, so a fixed amplitude either rails the ADC or leaves the pulse
+// buried in noise. `AutoGain` averages each channel over a short window and, when the average
+// leaves the target band, rescales that LED's amplitude towards the middle of the band. The
+// DC level is close to proportional to the LED current, so one correction usually lands.
+
+use embedded_hal::i2c::I2c;
+
+use crate::error::Error;
+use crate::max30102::{FifoSample, Max30102};
+
+// Samples are left-justified to 18 bits whatever the configured resolution
+const FULL_SCALE: u32 = 0x03FFFF;
+
+// Largest change applied in one step, keeps a single motion artefact from swinging the current
+const MAX_STEP_FACTOR: u32 = 2;
+
+// Channels in LED order: red (LED1), IR (LED2), green (LED3)
+const CHANNELS: usize = 3;
+
+pub struct AutoGain {
+    target_low: u32,
+    target_high: u32,
+    min_amplitude: u8,
+    max_amplitude: u8,
+    window: u16,
+    amplitudes: [u8; CHANNELS],
+    sums: [u64; CHANNELS],
+    counts: [u16; CHANNELS],
+}
+
+impl AutoGain {
+    // Averages over 25 samples (a quarter second at 100 Hz) and keeps the DC level between 25 %
+    // and 75 % of full scale. `initial_amplitude` must match what the LEDs are currently set to.
+    pub fn new(initial_amplitude: u8) -> Self {
+        AutoGain {
+            target_low: FULL_SCALE / 4,
+            target_high: FULL_SCALE / 4 * 3,
+            min_amplitude: 0x01,
+            max_amplitude: 0xFF,
+            window: 25,
+            amplitudes: [initial_amplitude; CHANNELS],
+            sums: [0; CHANNELS],
+            counts: [0; CHANNELS],
+        }
+    }
+
+    // Target band for the channel DC level, in raw 18-bit counts
+    pub fn with_target(mut self, low: u32, high: u32) -> Self {
+        self.target_low = low.min(high);
+        self.target_high = high.max(low);
+        self
+    }
+
+    // Amplitude register limits, 0.2 mA per step on the MAX30102
+    pub fn with_amplitude_limits(mut self, min: u8, max: u8) -> Self {
+        self.min_amplitude = min.min(max);
+        self.max_amplitude = max.max(min);
+        self
+    }
+
+    pub fn with_window(mut self, samples: u16) -> Self {
+        self.window = samples.max(1);
+        self
+    }
+
+    // Current amplitude of the red, IR and green LEDs as tracked by the controller
+    pub fn amplitudes(&self) -> [u8; CHANNELS] {
+        self.amplitudes
+    }
+
+    pub fn reset(&mut self) {
+        self.sums = [0; CHANNELS];
+        self.counts = [0; CHANNELS];
+    }
+
+    // Accumulate one sample. When a channel's window completes with its average outside the
+    // target band, the new amplitude for that LED is returned (red, IR, green order).
+    pub fn observe(&mut self, sample: &FifoSample) -> [Option<u8>; CHANNELS] {
+        let mut changes = [None; CHANNELS];
+        let values = [sample.red, sample.ir, sample.green];
+
+        for (channel, value) in values.into_iter().enumerate() {
+            let Some(value) = value else { continue };
+
+            self.sums[channel] += value as u64;
+            self.counts[channel] += 1;
+            if self.counts[channel] < self.window {
+                continue;
+            }
+
+            let average = (self.sums[channel] / self.counts[channel] as u64) as u32;
+            self.sums[channel] = 0;
+            self.counts[channel] = 0;
+
+            let amplitude = self.corrected_amplitude(self.amplitudes[channel], average);
+            if amplitude != self.amplitudes[channel] {
+                self.amplitudes[channel] = amplitude;
+                changes[channel] = Some(amplitude);
+            }
+        }
+        changes
+    }
+
+    // Observe a sample and write any amplitude change to the sensor. Returns true if an LED
+    // current was changed, in which case downstream filters should expect a step in the signal.
+    pub fn update<I2C, E>(&mut self, sensor: &mut Max30102<I2C>, sample: &FifoSample) -> Result<bool, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        let mut changed = false;
+        for (led, amplitude) in (1u8..).zip(self.observe(sample)) {
+            if let Some(amplitude) = amplitude {
+                sensor.set_led_pulse_amplitude(led, amplitude)?;
+                changed = true;
+            }
+        }
+        Ok(changed)
+    }
+
+    fn corrected_amplitude(&self, amplitude: u8, average: u32) -> u8 {
+        if (self.target_low..=self.target_high).contains(&average) {
+            return amplitude;
+        }
+
+        let current = amplitude.max(1) as u32;
+        let target = self.target_low / 2 + self.target_high / 2;
+        let scaled = if average == 0 {
+            current * MAX_STEP_FACTOR
+        } else {
+            (current as u64 * target as u64 / average as u64) as u32
+        };
+
+        let limited = scaled.clamp(current / MAX_STEP_FACTOR, current * MAX_STEP_FACTOR);
+        limited.clamp(self.min_amplitude as u32, self.max_amplitude as u32) as u8
+    }
+}
@@ -0,0 +1,125 @@
+use crate::error::Error;
+
+// Hook used to route the bus to a sensor before it is accessed, e.g. a TCA9548A I2C multiplexer.
+// Any `FnMut(u8) -> Result<(), E>` closure can be used directly.
+pub trait ChannelSelect<E> {
+    fn select_channel(&mut self, channel: u8) -> Result<(), E>;
+}
+
+impl<E, F> ChannelSelect<E> for F
+where
+    F: FnMut(u8) -> Result<(), E>,
+{
+    fn select_channel(&mut self, channel: u8) -> Result<(), E> {
+        self(channel)
+    }
+}
+
+// A fixed-size group of identical sensors, e.g. several MPU6050s at different addresses
+// or behind different mux channels. Every bulk operation reports a result per index so a
+// single failing sensor does not hide the state of the others.
+pub struct SensorArray<S, const N: usize> {
+    sensors: [S; N],
+    channels: [u8; N],
+}
+
+impl<S, const N: usize> SensorArray<S, N> {
+    // Sensor i is assigned mux channel i
+    pub fn new(sensors: [S; N]) -> Self {
+        SensorArray {
+            sensors,
+            channels: core::array::from_fn(|i| i as u8),
+        }
+    }
+
+    pub fn with_channels(sensors: [S; N], channels: [u8; N]) -> Self {
+        SensorArray { sensors, channels }
+    }
+
+    pub fn len(&self) -> usize {
+        N
+    }
+
+    pub fn is_empty(&self) -> bool {
+        N == 0
+    }
+
+    pub fn channel(&self, index: usize) -> Option<u8> {
+        self.channels.get(index).copied()
+    }
+
+    pub fn get_mut(&mut self, index: usize) -> Option<&mut S> {
+        self.sensors.get_mut(index)
+    }
+
+    pub fn sensors_mut(&mut self) -> &mut [S; N] {
+        &mut self.sensors
+    }
+
+    pub fn into_inner(self) -> [S; N] {
+        self.sensors
+    }
+
+    // Run an initialization routine on every sensor
+    pub fn init_all<E, F>(&mut self, init: F) -> [Result<(), Error<E>>; N]
+    where
+        F: FnMut(&mut S) -> Result<(), Error<E>>,
+    {
+        self.for_each(init)
+    }
+
+    // Read every sensor into a fixed-size array of per-index results
+    pub fn read_all<T, E, F>(&mut self, read: F) -> [Result<T, Error<E>>; N]
+    where
+        F: FnMut(&mut S) -> Result<T, Error<E>>,
+    {
+        self.for_each(read)
+    }
+
+    // Same as init_all, but selects each sensor's mux channel first
+    pub fn init_all_muxed<E, M, F>(&mut self, mux: &mut M, init: F) -> [Result<(), Error<E>>; N]
+    where
+        M: ChannelSelect<E>,
+        F: FnMut(&mut S) -> Result<(), Error<E>>,
+    {
+        self.for_each_muxed(mux, init)
+    }
+
+    // Same as read_all, but selects each sensor's mux channel first
+    pub fn read_all_muxed<T, E, M, F>(&mut self, mux: &mut M, read: F) -> [Result<T, Error<E>>; N]
+    where
+        M: ChannelSelect<E>,
+        F: FnMut(&mut S) -> Result<T, Error<E>>,
+    {
+        self.for_each_muxed(mux, read)
+    }
+
+    pub fn for_each<T, E, F>(&mut self, mut f: F) -> [Result<T, Error<E>>; N]
+    where
+        F: FnMut(&mut S) -> Result<T, Error<E>>,
+    {
+        let sensors = &mut self.sensors;
+        core::array::from_fn(|i| f(&mut sensors[i]))
+    }
+
+    pub fn for_each_muxed<T, E, M, F>(&mut self, mux: &mut M, mut f: F) -> [Result<T, Error<E>>; N]
+    where
+        M: ChannelSelect<E>,
+        F: FnMut(&mut S) -> Result<T, Error<E>>,
+    {
+        let sensors = &mut self.sensors;
+        let channels = &self.channels;
+        core::array::from_fn(|i| {
+            mux.select_channel(channels[i])?;
+            f(&mut sensors[i])
+        })
+    }
+}
+
+// Index and error of the first failed entry in a bulk result, if any
+pub fn first_error<T, E>(results: &[Result<T, Error<E>>]) -> Option<(usize, &Error<E>)> {
+    results
+        .iter()
+        .enumerate()
+        .find_map(|(index, result)| result.as_ref().err().map(|error| (index, error)))
+}
@@ -0,0 +1,26 @@
+// Devices supported by the crate, as detected from their identification registers
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Device {
+    Mpu6050,
+    Mpu6500,
+    Mpu6515,
+    Mpu6886,
+    Mpu9250,
+    Mpu9255,
+    Max30102,
+    Max30101,
+    Max30105,
+}
+
+// Identification data reported by a driver's `device_info()`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DeviceInfo {
+    pub device: Device,
+    pub part_id: u8,
+    pub revision: Option<u8>,   // None when the chip exposes no revision register
+    pub address: u8,
+}
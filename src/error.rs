@@ -1,11 +1,23 @@
-use core::fmt::{Debug, Formatter, Result};
+use core::fmt::{Debug, Display, Formatter, Result};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum Access {
+    Read,
+    Write,
+}
 
 #[derive(Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
 pub enum Error<E> {
     I2c(E),
+    // Bus error while accessing a known register, `reg` is the first register of the transfer
+    Register { reg: u8, access: Access, source: E },
     InvalidData,
     NotDetected,
     ConfigError,
+    Timeout,
+    FifoOverflow(u8),   // Samples the sensor dropped, as far as it counts them
     SensorSpecific(&'static str),
 }
 
@@ -16,27 +28,44 @@ impl<E> From<E> for Error<E> {
     }
 }
 
-impl<E> Debug for Error<E> 
-where 
-    E: Debug 
+// I2C error types from embedded-hal implementations only guarantee Debug, so that is what the
+// wrapped error is printed with
+impl<E> Display for Error<E>
+where
+    E: Debug
 {
-
     fn fmt(&self, f: &mut Formatter) -> Result {
         match self {
             Error::I2c(e) => write!(f, "I2C Error: {:?}", e),
+            Error::Register { reg, access: Access::Read, source } => write!(f, "I2C Error reading register 0x{:02X}: {:?}", reg, source),
+            Error::Register { reg, access: Access::Write, source } => write!(f, "I2C Error writing register 0x{:02X}: {:?}", reg, source),
             Error::InvalidData => write!(f, "Invalid Data received from sensor"),
             Error::NotDetected => write!(f, "Sensor not detected at address"),
             Error::ConfigError => write!(f, "Invalid Configuration"),
+            Error::Timeout => write!(f, "Timed out waiting for sensor"),
+            Error::FifoOverflow(dropped) => write!(f, "FIFO overflow, {} samples dropped", dropped),
             Error::SensorSpecific(msg) => write!(f, "Sensor Error: {}", msg),
         }
     }
 }
 
+impl<E> Debug for Error<E> 
+where 
+    E: Debug 
+{
+
+    fn fmt(&self, f: &mut Formatter) -> Result {
+        Display::fmt(self, f)
+    }
+}
+
+impl<E> core::error::Error for Error<E> where E: Debug {}
+
 // Additional convenience implementations
 impl<E> Error<E> {
 
     pub fn is_i2c_error(&self) -> bool {
-        matches!(self, Error::I2c(_))
+        matches!(self, Error::I2c(_) | Error::Register { .. })
     }
     
 
@@ -46,8 +75,27 @@ impl<E> Error<E> {
     
     pub fn into_i2c_error(self) -> Option<E> {
         match self {
-            Error::I2c(e) => Some(e),
+            Error::I2c(e) | Error::Register { source: e, .. } => Some(e),
+            _ => None,
+        }
+    }
+
+    // Register the failed transfer started at, when the driver recorded it
+    pub fn register(&self) -> Option<u8> {
+        match self {
+            Error::Register { reg, .. } => Some(*reg),
             _ => None,
         }
     }
+
+    // For drivers: `.map_err(Error::read(REG))?` attaches the register to a bus error
+    #[cfg(any(feature = "mpu6050", feature = "mpu9250", feature = "max30102"))]
+    pub(crate) fn read(reg: u8) -> impl FnOnce(E) -> Self {
+        move |source| Error::Register { reg, access: Access::Read, source }
+    }
+
+    #[cfg(any(feature = "mpu6050", feature = "mpu9250", feature = "max30102"))]
+    pub(crate) fn write(reg: u8) -> impl FnOnce(E) -> Self {
+        move |source| Error::Register { reg, access: Access::Write, source }
+    }
 }
@@ -0,0 +1,130 @@
+// Zero-copy views over raw sensor byte buffers.
+//
+// These types borrow a `&[u8]` (for example a DMA buffer or a chunk drained from a FIFO)
+// and decode individual fields on demand, so callers only pay for the bit math they use.
+
+#[cfg(any(feature = "mpu6050", feature = "mpu9250"))]
+pub const IMU_FRAME_SIZE: usize = 14;
+
+#[cfg(feature = "max30102")]
+pub const PPG_FRAME_SIZE: usize = 6;
+
+// View over the contiguous ACCEL_XOUT_H..GYRO_ZOUT_L block shared by the MPU6050 and MPU9250
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg(any(feature = "mpu6050", feature = "mpu9250"))]
+pub struct ImuFrameView<'a> {
+    bytes: &'a [u8],
+}
+
+#[cfg(any(feature = "mpu6050", feature = "mpu9250"))]
+impl<'a> ImuFrameView<'a> {
+    // Returns None if the buffer is shorter than one frame
+    pub fn new(bytes: &'a [u8]) -> Option<Self> {
+        if bytes.len() < IMU_FRAME_SIZE {
+            return None;
+        }
+        Some(ImuFrameView { bytes: &bytes[..IMU_FRAME_SIZE] })
+    }
+
+    fn word(&self, offset: usize) -> i16 {
+        ((self.bytes[offset] as i16) << 8) | self.bytes[offset + 1] as i16
+    }
+
+    pub fn accel_raw(&self) -> [i16; 3] {
+        [self.word(0), self.word(2), self.word(4)]
+    }
+
+    pub fn temp_raw(&self) -> i16 {
+        self.word(6)
+    }
+
+    pub fn gyro_raw(&self) -> [i16; 3] {
+        [self.word(8), self.word(10), self.word(12)]
+    }
+
+    pub fn acceleration(&self, accel_scale: f32) -> [f32; 3] {
+        let raw = self.accel_raw();
+        [raw[0] as f32 * accel_scale, raw[1] as f32 * accel_scale, raw[2] as f32 * accel_scale]
+    }
+
+    pub fn angular_velocity(&self, gyro_scale: f32) -> [f32; 3] {
+        let raw = self.gyro_raw();
+        [raw[0] as f32 * gyro_scale, raw[1] as f32 * gyro_scale, raw[2] as f32 * gyro_scale]
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+// View over a single 6-byte MAX30102 SpO2-mode FIFO sample (3 bytes Red + 3 bytes IR, LED1 first)
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg(feature = "max30102")]
+pub struct PpgFrameView<'a> {
+    bytes: &'a [u8],
+}
+
+#[cfg(feature = "max30102")]
+impl<'a> PpgFrameView<'a> {
+    // Returns None if the buffer is shorter than one sample
+    pub fn new(bytes: &'a [u8]) -> Option<Self> {
+        if bytes.len() < PPG_FRAME_SIZE {
+            return None;
+        }
+        Some(PpgFrameView { bytes: &bytes[..PPG_FRAME_SIZE] })
+    }
+
+    fn channel(&self, offset: usize) -> u32 {
+        (((self.bytes[offset] as u32) << 16) |
+            ((self.bytes[offset + 1] as u32) << 8) |
+            (self.bytes[offset + 2] as u32)) & 0x03FFFF   // Mask to 18-bits
+    }
+
+    pub fn red(&self) -> u32 {
+        self.channel(0)
+    }
+
+    pub fn ir(&self) -> u32 {
+        self.channel(3)
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.bytes
+    }
+}
+
+// View over a buffer holding several back-to-back PPG samples, e.g. a whole FIFO burst
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg(feature = "max30102")]
+pub struct PpgFramesView<'a> {
+    bytes: &'a [u8],
+}
+
+#[cfg(feature = "max30102")]
+impl<'a> PpgFramesView<'a> {
+    // Trailing bytes that do not form a complete sample are ignored
+    pub fn new(bytes: &'a [u8]) -> Self {
+        let whole = bytes.len() - bytes.len() % PPG_FRAME_SIZE;
+        PpgFramesView { bytes: &bytes[..whole] }
+    }
+
+    pub fn len(&self) -> usize {
+        self.bytes.len() / PPG_FRAME_SIZE
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.bytes.is_empty()
+    }
+
+    pub fn get(&self, index: usize) -> Option<PpgFrameView<'a>> {
+        let start = index.checked_mul(PPG_FRAME_SIZE)?;
+        self.bytes.get(start..start + PPG_FRAME_SIZE).and_then(PpgFrameView::new)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = PpgFrameView<'a>> + 'a {
+        self.bytes.chunks_exact(PPG_FRAME_SIZE).filter_map(PpgFrameView::new)
+    }
+}
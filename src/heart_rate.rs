@@ -0,0 +1,261 @@
+// Beat detection and heart rate from a MAX30102 PPG channel: IR in SpO2 mode, red in
+// HeartRate mode.
+//
+// Each heart beat briefly increases the blood volume under the sensor, which absorbs more light
+// and shows up as a dip in the reflected light. `HeartRateDetector` removes the DC level,
+// low-pass filters the remainder, inverts it so beats become peaks, and accepts a peak as a
+// beat when it rises above half of the recent beat amplitude and comes at least one refractory
+// period after the previous beat. All state is fixed-size; nothing allocates.
+//
+// Beat times are counted in samples from the first sample fed in and converted with the sample
+// rate. The peak position is refined with a parabola through the three samples around it, so
+// intervals are not quantised to whole samples (10 ms at 100 samples/s), which matters for HRV.
+
+use crate::math::Float;
+use crate::max30102::{SampleAveraging, SamplingRate};
+
+// Upper bound of the pass band, well above the fastest heart rate (220 BPM = 3.7 Hz)
+const LOW_PASS_CUTOFF_HZ: Float = 5.0;
+
+// Time constant of the DC tracker in seconds
+const DC_TIME_CONSTANT_S: Float = 1.5;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Beat {
+    pub sample_index: u32,            // Index of the sample that completed the peak
+    pub time_ms: Float,               // Interpolated peak time since the first sample
+    pub interval_ms: Option<Float>,   // Time since the previous beat, None for the first one
+    pub bpm: Option<Float>,           // Rate averaged over the recent intervals
+}
+
+// Ring of the last K intervals (in samples) and their average rate, shared by the beat and
+// breath detectors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct IntervalAverage<const K: usize> {
+    intervals: [Float; K],
+    head: usize,
+    count: usize,
+}
+
+impl<const K: usize> IntervalAverage<K> {
+    pub(crate) fn new() -> Self {
+        IntervalAverage { intervals: [0.0; K], head: 0, count: 0 }
+    }
+
+    pub(crate) fn push(&mut self, interval: Float) {
+        if K == 0 {
+            return;
+        }
+        self.intervals[self.head] = interval;
+        self.head = (self.head + 1) % K;
+        self.count = (self.count + 1).min(K);
+    }
+
+    // After a lost signal the older intervals no longer describe the current rate
+    pub(crate) fn clear(&mut self) {
+        self.head = 0;
+        self.count = 0;
+    }
+
+    // Events per minute over the stored intervals, None until one has been pushed
+    pub(crate) fn per_minute(&self, sample_rate: Float) -> Option<Float> {
+        if self.count == 0 {
+            return None;
+        }
+        let total: Float = self.intervals[..self.count].iter().sum();
+        Some(60.0 * sample_rate * self.count as Float / total)
+    }
+}
+
+// `K` is the number of beat intervals averaged into the reported rate
+pub struct HeartRateDetector<const K: usize = 4> {
+    sample_rate: Float,
+    min_interval: u32,   // Refractory period in samples (220 BPM)
+    max_interval: u32,   // Longer gaps are treated as lost signal (30 BPM)
+    dc_alpha: Float,
+    low_pass_alpha: Float,
+    amplitude_decay: Float,
+
+    dc: Option<Float>,
+    filtered: Float,
+    previous: Float,
+    before_previous: Float,
+    rising: bool,
+    amplitude: Float,
+    index: u32,
+    last_beat: Option<(u32, Float)>,   // Sample index and interpolated offset of the last peak
+    intervals: IntervalAverage<K>,
+}
+
+impl<const K: usize> HeartRateDetector<K> {
+    // `sample_rate` is the effective rate of the samples fed in, i.e. the MAX30102 sampling
+    // rate divided by the FIFO averaging factor
+    pub fn new(sample_rate: Float) -> Self {
+        let sample_rate = if sample_rate > 0.0 { sample_rate } else { 1.0 };
+        let dt = 1.0 / sample_rate;
+        let rc = 1.0 / (2.0 * core::f32::consts::PI as Float * LOW_PASS_CUTOFF_HZ);
+
+        HeartRateDetector {
+            sample_rate,
+            min_interval: (sample_rate * 60.0 / 220.0) as u32,
+            max_interval: (sample_rate * 60.0 / 30.0) as u32,
+            dc_alpha: (dt / DC_TIME_CONSTANT_S).min(1.0),
+            low_pass_alpha: dt / (rc + dt),
+            // Lets the threshold fall to half within about two seconds when beats get weaker
+            amplitude_decay: 1.0 - 0.35 * dt,
+            dc: None,
+            filtered: 0.0,
+            previous: 0.0,
+            before_previous: 0.0,
+            rising: false,
+            amplitude: 0.0,
+            index: 0,
+            last_beat: None,
+            intervals: IntervalAverage::new(),
+        }
+    }
+
+    // Detector for samples read straight from the FIFO with the given sensor settings
+    pub fn for_sensor(rate: SamplingRate, averaging: SampleAveraging) -> Self {
+        Self::new(rate.hz() as Float / averaging.samples() as Float)
+    }
+
+    pub fn sample_rate(&self) -> Float {
+        self.sample_rate
+    }
+
+    // Feed one sample, returns a beat when this sample completed one
+    pub fn update(&mut self, sample: u32) -> Option<Beat> {
+        let value = sample as Float;
+        let dc = match self.dc {
+            Some(dc) => dc + (value - dc) * self.dc_alpha,
+            None => value,
+        };
+        self.dc = Some(dc);
+
+        // Inverted so that the absorption dip of a beat becomes a positive peak
+        self.filtered += (dc - value - self.filtered) * self.low_pass_alpha;
+        self.amplitude *= self.amplitude_decay;
+
+        let index = self.index;
+        self.index = self.index.wrapping_add(1);
+
+        let before_peak = self.before_previous;
+        let peak = self.previous;
+        let was_rising = self.rising;
+        self.rising = self.filtered > self.previous;
+        self.before_previous = self.previous;
+        self.previous = self.filtered;
+
+        // A peak ends on the first sample that stops rising
+        if !was_rising || self.rising || peak <= 0.0 || peak < self.amplitude * 0.5 {
+            return None;
+        }
+
+        let samples = self.last_beat.map(|(last, _)| index.wrapping_sub(last));
+        if samples.is_some_and(|samples| samples < self.min_interval) {
+            return None;
+        }
+
+        // The peak is the previous sample; the vertex of the parabola through it and its two
+        // neighbours lies within half a sample of it
+        let curvature = before_peak - 2.0 * peak + self.filtered;
+        let vertex = if curvature < 0.0 { (0.5 * (before_peak - self.filtered) / curvature).clamp(-0.5, 0.5) } else { 0.0 };
+        let offset = vertex - 1.0;
+
+        self.amplitude = if self.amplitude > 0.0 { self.amplitude * 0.75 + peak * 0.25 } else { peak };
+        let last_beat = self.last_beat.replace((index, offset));
+
+        let interval = samples
+            .filter(|&samples| samples <= self.max_interval)
+            .zip(last_beat)
+            .map(|(samples, (_, last_offset))| samples as Float + offset - last_offset);
+        match interval {
+            Some(interval) => self.intervals.push(interval),
+            None => self.intervals.clear(),
+        }
+
+        Some(Beat {
+            sample_index: index,
+            time_ms: self.samples_to_ms(index as Float + offset),
+            interval_ms: interval.map(|interval| self.samples_to_ms(interval)),
+            bpm: self.bpm(),
+        })
+    }
+
+    // Average rate over the last K intervals, None until one interval has been measured
+    pub fn bpm(&self) -> Option<Float> {
+        self.intervals.per_minute(self.sample_rate)
+    }
+
+    // Time of a sample index (as counted by this detector) in milliseconds
+    pub fn samples_to_ms(&self, samples: Float) -> Float {
+        samples * 1000.0 / self.sample_rate
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(self.sample_rate);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    extern crate std;
+
+    use super::*;
+
+    const SAMPLE_RATE: Float = 100.0;
+    const PULSE_HZ: Float = 1.2;
+
+    // PPG-like IR signal: a DC level with a Gaussian absorption dip per beat, plus an optional
+    // second, smaller dip `echo_s` seconds after each one
+    fn ppg(index: u32, echo_s: Option<Float>) -> u32 {
+        let period = 1.0 / PULSE_HZ;
+        let t = index as Float / SAMPLE_RATE;
+        let dip = |delay: Float| {
+            let phase = (t - delay).rem_euclid(period);
+            let distance = phase.min(period - phase) / 0.04;
+            (-0.5 * distance * distance).exp()
+        };
+        let depth = 1_000.0 * dip(0.0) + echo_s.map_or(0.0, |echo| 800.0 * dip(echo));
+        (100_000.0 - depth) as u32
+    }
+
+    fn detect(echo_s: Option<Float>, seconds: u32) -> (HeartRateDetector, std::vec::Vec<Beat>) {
+        let mut detector = HeartRateDetector::new(SAMPLE_RATE);
+        let beats = (0..seconds * SAMPLE_RATE as u32).filter_map(|index| detector.update(ppg(index, echo_s))).collect();
+        (detector, beats)
+    }
+
+    #[test]
+    fn reports_72_bpm_for_a_1_2_hz_pulse() {
+        let (detector, beats) = detect(None, 20);
+        let bpm = detector.bpm().unwrap();
+        assert!((bpm - 72.0).abs() < 1.0, "{bpm} BPM");
+
+        // One beat per period once the DC tracker has settled
+        assert!((22..=24).contains(&beats.len()), "{} beats", beats.len());
+        for interval in beats.iter().skip(3).filter_map(|beat| beat.interval_ms) {
+            assert!((interval - 833.3).abs() < 20.0, "{interval} ms");
+        }
+    }
+
+    #[test]
+    fn second_peak_outside_refractory_period_counts() {
+        // 350 ms apart is beyond the 272 ms refractory period, so both dips are beats
+        let (_, beats) = detect(Some(0.35), 20);
+        assert!(beats.len() > 40, "{} beats", beats.len());
+    }
+
+    #[test]
+    fn refractory_period_rejects_double_peaks() {
+        // The same echo 150 ms after each beat falls inside the refractory period
+        let (detector, beats) = detect(Some(0.15), 20);
+        let bpm = detector.bpm().unwrap();
+        assert!((bpm - 72.0).abs() < 1.0, "{bpm} BPM");
+        assert!(beats.iter().filter_map(|beat| beat.interval_ms).all(|interval| interval >= 272.0));
+        assert!((22..=24).contains(&beats.len()), "{} beats", beats.len());
+    }
+}
@@ -0,0 +1,227 @@
+// Heart rate variability from beat-to-beat (RR) intervals.
+//
+// `HrvWindow` keeps the last N RR intervals, fed either directly in milliseconds or from the
+// beats reported by `HeartRateDetector`, and computes the usual time-domain metrics:
+//   SDNN  - standard deviation of the intervals
+//   RMSSD - root mean square of successive differences
+//   pNN50 - share of successive differences larger than 50 ms
+// Successive differences are only taken between intervals that were measured back to back,
+// so a gap in the signal does not show up as a huge jump.
+
+use crate::heart_rate::Beat;
+use crate::math::{self, Float};
+
+// Intervals outside this range (220 and 30 BPM, the limits of HeartRateDetector) are artifacts, not beats
+const RR_MIN_MS: Float = 273.0;
+const RR_MAX_MS: Float = 2000.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct HrvMetrics {
+    pub mean_rr_ms: Float,
+    pub mean_bpm: Float,
+    pub sdnn_ms: Float,
+    pub rmssd_ms: Float,
+    pub pnn50: Float,     // Percent
+    pub intervals: usize, // Number of RR intervals the metrics were computed from
+}
+
+pub struct HrvWindow<const N: usize> {
+    intervals: [Float; N],
+    contiguous: [bool; N],   // Interval directly follows the previous one, no gap in between
+    head: usize,
+    len: usize,
+    follows_previous: bool,
+}
+
+impl<const N: usize> Default for HrvWindow<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> HrvWindow<N> {
+    pub fn new() -> Self {
+        HrvWindow {
+            intervals: [0.0; N],
+            contiguous: [false; N],
+            head: 0,
+            len: 0,
+            follows_previous: false,
+        }
+    }
+
+    // Add one RR interval. Implausible intervals are dropped and break the chain of successive
+    // differences. Returns false if the interval was rejected.
+    pub fn push_interval(&mut self, rr_ms: Float) -> bool {
+        if N == 0 || !(RR_MIN_MS..=RR_MAX_MS).contains(&rr_ms) {
+            self.follows_previous = false;
+            return false;
+        }
+
+        self.intervals[self.head] = rr_ms;
+        self.contiguous[self.head] = self.follows_previous;
+        self.head = (self.head + 1) % N;
+        self.len = (self.len + 1).min(N);
+        self.follows_previous = true;
+        true
+    }
+
+    // Add the interval carried by a detected beat. A beat without an interval (first beat or
+    // after lost signal) starts a new chain.
+    pub fn push_beat(&mut self, beat: &Beat) -> bool {
+        match beat.interval_ms {
+            Some(rr_ms) => self.push_interval(rr_ms),
+            None => {
+                self.follows_previous = false;
+                false
+            }
+        }
+    }
+
+    // Mark a gap, e.g. after the finger was lifted, without clearing the window
+    pub fn mark_gap(&mut self) {
+        self.follows_previous = false;
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn reset(&mut self) {
+        self.head = 0;
+        self.len = 0;
+        self.follows_previous = false;
+    }
+
+    // Metrics over the stored intervals, None until there are at least two successive ones
+    pub fn metrics(&self) -> Option<HrvMetrics> {
+        let mut sum = 0.0;
+        let mut successive_sq = 0.0;
+        let mut successive = 0usize;
+        let mut over_50ms = 0usize;
+        let mut previous: Option<Float> = None;
+
+        for (rr, contiguous) in self.ordered() {
+            sum += rr;
+            if let (Some(previous), true) = (previous, contiguous) {
+                let difference = rr - previous;
+                successive_sq += difference * difference;
+                successive += 1;
+                if math::abs(difference) > 50.0 {
+                    over_50ms += 1;
+                }
+            }
+            previous = Some(rr);
+        }
+
+        if successive == 0 {
+            return None;
+        }
+
+        let count = self.len as Float;
+        let mean = sum / count;
+        let variance = self.ordered().map(|(rr, _)| (rr - mean) * (rr - mean)).sum::<Float>() / (count - 1.0);
+
+        Some(HrvMetrics {
+            mean_rr_ms: mean,
+            mean_bpm: 60_000.0 / mean,
+            sdnn_ms: math::sqrt(variance),
+            rmssd_ms: math::sqrt(successive_sq / successive as Float),
+            pnn50: 100.0 * over_50ms as Float / successive as Float,
+            intervals: self.len,
+        })
+    }
+
+    // Stored intervals oldest first. The oldest entry's predecessor has been overwritten, so it
+    // never counts as contiguous.
+    fn ordered(&self) -> impl Iterator<Item = (Float, bool)> + '_ {
+        let start = (self.head + N - self.len) % N.max(1);
+        (0..self.len).map(move |offset| {
+            let index = (start + offset) % N;
+            (self.intervals[index], offset > 0 && self.contiguous[index])
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Relative tolerance. micromath's square root is an approximation good to a few percent.
+    #[cfg(feature = "libm")]
+    const TOLERANCE: Float = 1e-4;
+    #[cfg(not(feature = "libm"))]
+    const TOLERANCE: Float = 0.06;
+
+    fn assert_close(actual: Float, expected: Float) {
+        assert!(math::abs(actual - expected) <= TOLERANCE * expected, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn metrics_match_hand_computed_values() {
+        let mut window = HrvWindow::<8>::new();
+        for rr_ms in [800.0, 850.0, 780.0, 900.0] {
+            assert!(window.push_interval(rr_ms));
+        }
+
+        // Mean 832.5 ms; deviations -32.5, 17.5, -52.5, 67.5 give SDNN = sqrt(8675 / 3).
+        // Successive differences 50, -70, 120 give RMSSD = sqrt(21800 / 3), and two of the
+        // three exceed 50 ms.
+        let metrics = window.metrics().unwrap();
+        assert_close(metrics.mean_rr_ms, 832.5);
+        assert_close(metrics.mean_bpm, 72.072);
+        assert_close(metrics.sdnn_ms, 53.774);
+        assert_close(metrics.rmssd_ms, 85.245);
+        assert_close(metrics.pnn50, 66.667);
+        assert_eq!(metrics.intervals, 4);
+    }
+
+    #[test]
+    fn gap_breaks_successive_differences() {
+        let mut window = HrvWindow::<8>::new();
+        window.push_interval(800.0);
+        window.push_interval(850.0);
+        window.mark_gap();
+        window.push_interval(780.0);
+        window.push_interval(900.0);
+
+        // SDNN still covers all four intervals, but the 850 -> 780 step is not a successive
+        // difference: RMSSD = sqrt((50^2 + 120^2) / 2), one of two over 50 ms
+        let metrics = window.metrics().unwrap();
+        assert_close(metrics.sdnn_ms, 53.774);
+        assert_close(metrics.rmssd_ms, 91.924);
+        assert_close(metrics.pnn50, 50.0);
+    }
+
+    #[test]
+    fn rejected_interval_breaks_successive_differences() {
+        let mut window = HrvWindow::<8>::new();
+        window.push_interval(800.0);
+        assert!(!window.push_interval(150.0));
+        window.push_interval(850.0);
+
+        // Two stored intervals, but never back to back
+        assert_eq!(window.len(), 2);
+        assert_eq!(window.metrics(), None);
+    }
+
+    #[test]
+    fn oldest_interval_loses_its_predecessor_on_wrap() {
+        let mut window = HrvWindow::<3>::new();
+        for rr_ms in [1000.0, 800.0, 850.0, 780.0] {
+            window.push_interval(rr_ms);
+        }
+
+        // 800, 850, 780 remain; the 1000 -> 800 difference left with the overwritten interval
+        let metrics = window.metrics().unwrap();
+        assert_eq!(metrics.intervals, 3);
+        assert_close(metrics.rmssd_ms, 60.828);
+        assert_close(metrics.pnn50, 50.0);
+    }
+}
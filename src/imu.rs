@@ -0,0 +1,36 @@
+// Driver-independent access to the MPU family.
+//
+// The MPU6050 and MPU9250 drivers expose the same accelerometer, gyroscope and temperature
+// readings with the same units, but as inherent methods on unrelated types. `Imu` collects
+// them so orientation and motion code can be written once:
+//
+//   fn level<I: Imu<E>, E>(imu: &mut I) -> Result<bool, Error<E>> {
+//       let [x, y, _] = imu.read_acceleration()?;
+//       Ok(x.abs() < 0.05 && y.abs() < 0.05)
+//   }
+//
+// Ranges stay driver specific, each driver keeps its own enums, so code that has to change
+// them takes the range from the caller as `I::AccelRange` / `I::GyroRange`.
+
+use crate::error::Error;
+
+pub trait Imu<E> {
+    type AccelRange: Copy + PartialEq;
+    type GyroRange: Copy + PartialEq;
+
+    // In g, with the driver's calibration applied
+    fn read_acceleration(&mut self) -> Result<[f32; 3], Error<E>>;
+
+    // In degrees per second, with the driver's calibration applied
+    fn read_angular_velocity(&mut self) -> Result<[f32; 3], Error<E>>;
+
+    // Die temperature in degrees Celsius, None while the driver has the temperature sensor disabled
+    fn read_temperature(&mut self) -> Result<Option<f32>, Error<E>>;
+
+    fn set_accel_range(&mut self, range: Self::AccelRange) -> Result<(), Error<E>>;
+    fn set_gyro_range(&mut self, range: Self::GyroRange) -> Result<(), Error<E>>;
+
+    // Ranges the readings are currently scaled for, without a bus transfer
+    fn accel_range(&self) -> Self::AccelRange;
+    fn gyro_range(&self) -> Self::GyroRange;
+}
@@ -1,21 +1,71 @@
 #![no_std]
-#![no_main]
+#![cfg_attr(not(test), no_main)]
 
+pub mod array;
+pub mod device;
 pub mod error;
+pub mod frame;
+pub mod math;
+pub mod validation;
+pub mod watchdog;
+
+#[cfg(any(feature = "libm", feature = "micromath"))]
+pub mod tilt;
+
+#[cfg(any(feature = "mpu6050", feature = "mpu9250"))]
+pub mod imu;
+
+#[cfg(any(feature = "mpu6050", feature = "mpu9250"))]
+mod mpu_common;
 
 #[cfg(feature = "mpu9250")]
 pub mod mpu9250;
 
+#[cfg(feature = "mpu9250")]
+pub mod mag_calibration;
+
 #[cfg(feature = "mpu6050")]
 pub mod mpu6050;
 
 #[cfg(feature = "max30102")]
 pub mod max30102;
 
+#[cfg(feature = "max30102")]
+pub mod agc;
+
+#[cfg(feature = "max30102")]
+pub mod heart_rate;
+
+#[cfg(all(feature = "max30102", any(feature = "libm", feature = "micromath")))]
+pub mod hrv;
+
+#[cfg(feature = "max30102")]
+pub mod ppg_filter;
+
+#[cfg(all(feature = "max30102", any(feature = "libm", feature = "micromath")))]
+pub mod power_scheduler;
+
+#[cfg(all(feature = "max30102", any(feature = "libm", feature = "micromath")))]
+pub mod pulse_oximeter;
+
+#[cfg(feature = "max30102")]
+pub mod respiration;
+
+#[cfg(all(feature = "max30102", any(feature = "libm", feature = "micromath")))]
+pub mod signal_quality;
+
+#[cfg(all(feature = "max30102", any(feature = "libm", feature = "micromath")))]
+pub mod spo2;
+
 pub use error::Error;
+pub use device::{Device, DeviceInfo};
 
 pub mod prelude {
     pub use crate::error::Error;
+    pub use crate::array::SensorArray;
+    pub use crate::device::{Device, DeviceInfo};
+    #[cfg(any(feature = "mpu6050", feature = "mpu9250"))]
+    pub use crate::imu::Imu;
     #[cfg(feature = "mpu9250")]
     pub use crate::mpu9250;
 
@@ -30,18 +80,17 @@ pub mod prelude {
 pub mod mpu9250_hayasen {
     use super::mpu9250;
     use super::error::Error;
+    use embedded_hal::delay::DelayNs;
     use embedded_hal::i2c::I2c;
 
     pub fn create_default<I2C, E>(i2c: I2C, address: u8) -> Result<mpu9250::Mpu9250<I2C>, Error<E>>
     where
         I2C: I2c<Error = E>,
     {
-        let mut sensor = mpu9250::Mpu9250::new(i2c, address);
-        sensor.initialize_sensor(
+        mpu9250::Mpu9250::new(i2c, address).configure(
             mpu9250::AccelRange::Range2G,
             mpu9250::GyroRange::Range250Dps,
-        )?;
-        Ok(sensor)
+        )
     }
 
     pub fn read_acceleration<I2C, E>(sensor: &mut mpu9250::Mpu9250<I2C>) -> Result<[f32; 3], Error<E>>
@@ -69,10 +118,58 @@ pub mod mpu9250_hayasen {
     where
         I2C: I2c<Error = E>,
     {
-        let temp = sensor.read_temperature_celsius()?;
-        let accel = sensor.read_acceleration()?;
-        let gyro = sensor.read_angular_velocity()?;
-        Ok((temp, accel, gyro))
+        // Single burst read keeps the three channels coherent
+        let sample = sensor.read_sample()?;
+        Ok((sample.temperature, sample.acceleration, sample.angular_velocity))
+    }
+
+    pub fn read_sample<I2C, E>(sensor: &mut mpu9250::Mpu9250<I2C>) -> Result<mpu9250::Sample, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        sensor.read_sample()
+    }
+
+    pub fn read_magnetic_field<I2C, E>(sensor: &mut mpu9250::Mpu9250<I2C>) -> Result<[f32; 3], Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        sensor.read_magnetic_field()
+    }
+
+    pub fn read_9dof<I2C, E>(sensor: &mut mpu9250::Mpu9250<I2C>) -> Result<mpu9250::NineDofSample, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        sensor.read_9dof()
+    }
+
+    pub fn setup_wake_on_motion<I2C, E>(sensor: &mut mpu9250::Mpu9250<I2C>, threshold_mg: u16) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        sensor.enter_wake_on_motion(threshold_mg, mpu9250::LowPowerOdr::Rate31_25Hz)
+    }
+
+    pub fn enable_data_ready_interrupt<I2C, E>(sensor: &mut mpu9250::Mpu9250<I2C>) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        sensor.enable_interrupt(mpu9250::InterruptSource::DataReady)
+    }
+
+    pub fn setup_low_power_mode<I2C, E>(sensor: &mut mpu9250::Mpu9250<I2C>, odr: mpu9250::LowPowerOdr) -> Result<(), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        sensor.enter_low_power_accel_mode(odr)
+    }
+
+    pub fn calibrate<I2C, E>(sensor: &mut mpu9250::Mpu9250<I2C>, delay: &mut impl DelayNs) -> Result<mpu9250::Calibration, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        sensor.calibrate(500, delay)
     }
 }
 
@@ -82,6 +179,11 @@ pub mod mpu6050_hayasen {
     use super::error::Error;
     use embedded_hal::i2c::I2c;
 
+    #[cfg(any(feature = "libm", feature = "micromath"))]
+    use super::math::Float;
+    #[cfg(any(feature = "libm", feature = "micromath"))]
+    use super::tilt;
+
     pub fn create_default<I2C, E>(i2c: I2C, address: u8) -> Result<mpu6050::Mpu6050<I2C>, Error<E>>
     where
         I2C: I2c<Error = E>,
@@ -94,6 +196,15 @@ pub mod mpu6050_hayasen {
         Ok(sensor)
     }
 
+    // Probe both AD0 addresses and initialize whichever one answers
+    pub fn create_default_probed<I2C, E>(mut i2c: I2C) -> Result<mpu6050::Mpu6050<I2C>, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        let address = mpu6050::Mpu6050::probe(&mut i2c)?;
+        create_default(i2c, address.into())
+    }
+
     pub fn create_default_with_config<I2C, E>(
         i2c: I2C, 
         address: u8, 
@@ -122,30 +233,66 @@ pub mod mpu6050_hayasen {
         sensor.read_angular_velocity()
     }
 
-    pub fn read_temperature<I2C, E>(sensor: &mut mpu6050::Mpu6050<I2C>) -> Result<f32, Error<E>>
+    pub fn read_temperature<I2C, E>(sensor: &mut mpu6050::Mpu6050<I2C>) -> Result<Option<f32>, Error<E>>
     where
         I2C: I2c<Error = E>,
     {
         sensor.read_temperature_celsius()
     }
 
-    pub fn read_all<I2C, E>(sensor: &mut mpu6050::Mpu6050<I2C>) -> Result<(f32, [f32; 3], [f32; 3]), Error<E>>
+    // (temperature, acceleration, angular velocity); temperature is None while the sensor is disabled
+    pub type Reading = (Option<f32>, [f32; 3], [f32; 3]);
+
+    pub fn read_all<I2C, E>(sensor: &mut mpu6050::Mpu6050<I2C>) -> Result<Reading, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        // Single burst read keeps the three channels coherent
+        let sample = sensor.read_sample()?;
+        Ok((sample.temperature, sample.acceleration, sample.angular_velocity))
+    }
+
+    pub fn read_sample<I2C, E>(sensor: &mut mpu6050::Mpu6050<I2C>) -> Result<mpu6050::Sample, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        sensor.read_sample()
+    }
+
+    // Roll and pitch in degrees from the accelerometer alone. Only accurate while the sensor is
+    // not accelerating; use `read_roll_pitch_filtered` for moving platforms.
+    #[cfg(any(feature = "libm", feature = "micromath"))]
+    pub fn read_roll_pitch<I2C, E>(sensor: &mut mpu6050::Mpu6050<I2C>) -> Result<(Float, Float), Error<E>>
     where
         I2C: I2c<Error = E>,
     {
-        let temp = sensor.read_temperature_celsius()?;
         let accel = sensor.read_acceleration()?;
-        let gyro = sensor.read_angular_velocity()?;
-        Ok((temp, accel, gyro))
+        Ok(tilt::accel_roll_pitch(accel))
+    }
+
+    // Roll and pitch in degrees, smoothed with the gyro. `dt` is the time in seconds since the
+    // previous call with the same filter.
+    #[cfg(any(feature = "libm", feature = "micromath"))]
+    pub fn read_roll_pitch_filtered<I2C, E>(
+        sensor: &mut mpu6050::Mpu6050<I2C>,
+        filter: &mut tilt::TiltFilter,
+        dt: Float,
+    ) -> Result<(Float, Float), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        let sample = sensor.read_sample()?;
+        Ok(filter.update(sample.acceleration, sample.angular_velocity, dt))
     }
 
     // Additional MPU6050-specific convenience functions
+    // 5 Hz output with the narrowest DLPF; `set_output_data_rate()` derives the divider from the
+    // DLPF state, so the rate cannot silently end up on the 8 kHz base
     pub fn setup_low_power_mode<I2C, E>(sensor: &mut mpu6050::Mpu6050<I2C>) -> Result<(), Error<E>>
     where
         I2C: I2c<Error = E>,
     {
-        sensor.set_dlpf_config(mpu6050::DlpfConfig::Bandwidth5Hz)?;
-        sensor.set_sample_rate(199)?; // 5Hz sample rate (1000Hz/(199+1))
+        sensor.set_output_data_rate(5)?;
         Ok(())
     }
 
@@ -154,7 +301,7 @@ pub mod mpu6050_hayasen {
         I2C: I2c<Error = E>,
     {
         sensor.set_dlpf_config(mpu6050::DlpfConfig::Bandwidth260Hz)?;
-        sensor.set_sample_rate(7)?; // 125Hz sample rate (1000Hz/(7+1))
+        sensor.set_sample_rate(7)?; // 1kHz sample rate (8000Hz/(7+1), the 260Hz setting disables the DLPF)
         Ok(())
     }
 
@@ -177,6 +324,7 @@ pub mod mpu6050_hayasen {
 pub mod max30102_hayasen {
     use super::max30102;
     use super::error::Error;
+    use embedded_hal::delay::DelayNs;
     use embedded_hal::i2c::I2c;
 
     pub fn create_default<I2C, E>(i2c: I2C, address: u8) -> Result<max30102::Max30102<I2C>, Error<E>>
@@ -206,6 +354,23 @@ pub mod max30102_hayasen {
         Ok(sensor)
     }
 
+    // E.g. red, IR and green on a MAX30101:
+    //   create_multi_led_mode(i2c, 0x57, ChipVariant::Max30101, [Led1Red, Led2Ir, Led3Green, None], [0x1F; 3])
+    pub fn create_multi_led_mode<I2C, E>(
+        i2c: I2C,
+        address: u8,
+        variant: max30102::ChipVariant,
+        slots: [max30102::LedSlot; 4],
+        amplitudes: [u8; 3],
+    ) -> Result<max30102::Max30102<I2C>, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        let mut sensor = max30102::Max30102::new_with_variant(i2c, address, variant);
+        sensor.initialize_multi_led_mode(slots, amplitudes)?;
+        Ok(sensor)
+    }
+
     pub fn read_fifo_sample<I2C, E>(sensor: &mut max30102::Max30102<I2C>) -> Result<Option<max30102::FifoSample>, Error<E>>
     where
         I2C: I2c<Error = E>,
@@ -220,6 +385,13 @@ pub mod max30102_hayasen {
         sensor.read_fifo_batch(samples)
     }
 
+    pub fn read_batch<const N: usize, I2C, E>(sensor: &mut max30102::Max30102<I2C>) -> Result<([max30102::FifoSample; N], usize), Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        sensor.read_batch::<N>()
+    }
+
     pub fn read_temperature<I2C, E>(sensor: &mut max30102::Max30102<I2C>) -> Result<Option<f32>, Error<E>>
     where
         I2C: I2c<Error = E>,
@@ -227,6 +399,14 @@ pub mod max30102_hayasen {
         sensor.read_temperature()
     }
 
+    // Blocks for about 30 ms while the die temperature is converted
+    pub fn measure_temperature<I2C, E>(sensor: &mut max30102::Max30102<I2C>, delay: &mut impl DelayNs) -> Result<f32, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        sensor.measure_temperature_blocking(delay)
+    }
+
     pub fn get_available_samples<I2C, E>(sensor: &mut max30102::Max30102<I2C>) -> Result<u8, Error<E>>
     where
         I2C: I2c<Error = E>,
@@ -244,8 +424,8 @@ pub mod max30102_hayasen {
         // Configure for low power: lower sampling rate, higher averaging, lower LED power
         sensor.set_sampling_rate(max30102::SamplingRate::Rate50)?;
         sensor.set_sample_averaging(max30102::SampleAveraging::Average16)?;
-        sensor.set_led_pulse_amplitude(1, 0x0F)?; // Lower Red LED power
-        sensor.set_led_pulse_amplitude(2, 0x0F)?; // Lower IR LED power
+        sensor.set_led_current_ma(1, 3.0)?; // Lower Red LED power
+        sensor.set_led_current_ma(2, 3.0)?; // Lower IR LED power
         Ok(())
     }
 
@@ -256,8 +436,8 @@ pub mod max30102_hayasen {
         // Configure for high performance: higher sampling rate, lower averaging, higher LED power
         sensor.set_sampling_rate(max30102::SamplingRate::Rate400)?;
         sensor.set_sample_averaging(max30102::SampleAveraging::Average2)?;
-        sensor.set_led_pulse_amplitude(1, 0x3F)?; // Higher Red LED power
-        sensor.set_led_pulse_amplitude(2, 0x3F)?; // Higher IR LED power
+        sensor.set_led_current_ma(1, 12.6)?; // Higher Red LED power
+        sensor.set_led_current_ma(2, 12.6)?; // Higher IR LED power
         Ok(())
     }
 
@@ -265,9 +445,7 @@ pub mod max30102_hayasen {
     where
         I2C: I2c<Error = E>,
     {
-        sensor.set_proximity_threshold(threshold)?;
-        sensor.enable_interrupt(max30102::InterruptSource::AlcOverflow)?;
-        Ok(())
+        sensor.enable_proximity_mode(0x1F, threshold)   // Pilot LED at 6.2 mA
     }
 
     pub fn enable_data_ready_interrupt<I2C, E>(sensor: &mut max30102::Max30102<I2C>) -> Result<(), Error<E>>
@@ -293,7 +471,7 @@ pub mod max30102_hayasen {
         sensor.start_temperature_measurement()
     }
 
-    pub fn check_sensor_status<I2C, E>(sensor: &mut max30102::Max30102<I2C>) -> Result<(bool, max30102::OperationMode, (u8, u8)), Error<E>>
+    pub fn check_sensor_status<I2C, E>(sensor: &mut max30102::Max30102<I2C>) -> Result<(bool, max30102::OperationMode, max30102::InterruptStatus), Error<E>>
     where
         I2C: I2c<Error = E>,
     {
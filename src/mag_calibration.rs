@@ -0,0 +1,104 @@
+// Hard-iron / soft-iron magnetometer calibration.
+//
+// Feed `MagCalibrator` with readings while the device is slowly rotated through every
+// orientation. The min/max envelope gives the hard-iron offset (center of the envelope) and a
+// per-axis soft-iron scale that equalizes the envelope's extents.
+
+// Correction applied as: corrected = (measured - hard_iron) * soft_iron
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MagCalibration {
+    pub hard_iron: [f32; 3],   // uT
+    pub soft_iron: [f32; 3],   // Unitless scale factors
+}
+
+impl Default for MagCalibration {
+    fn default() -> Self {
+        MagCalibration {
+            hard_iron: [0.0; 3],
+            soft_iron: [1.0; 3],
+        }
+    }
+}
+
+impl MagCalibration {
+    pub fn apply(&self, field: [f32; 3]) -> [f32; 3] {
+        [
+            (field[0] - self.hard_iron[0]) * self.soft_iron[0],
+            (field[1] - self.hard_iron[1]) * self.soft_iron[1],
+            (field[2] - self.hard_iron[2]) * self.soft_iron[2],
+        ]
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct MagCalibrator {
+    min: [f32; 3],
+    max: [f32; 3],
+    samples: u32,
+}
+
+impl Default for MagCalibrator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl MagCalibrator {
+    pub fn new() -> Self {
+        MagCalibrator {
+            min: [f32::MAX; 3],
+            max: [f32::MIN; 3],
+            samples: 0,
+        }
+    }
+
+    pub fn add_sample(&mut self, field: [f32; 3]) {
+        for (axis, &value) in field.iter().enumerate() {
+            if value < self.min[axis] {
+                self.min[axis] = value;
+            }
+            if value > self.max[axis] {
+                self.max[axis] = value;
+            }
+        }
+        self.samples += 1;
+    }
+
+    pub fn sample_count(&self) -> u32 {
+        self.samples
+    }
+
+    // Smallest min/max span over the three axes; a well-rotated device covers roughly twice the
+    // local field strength (about 50-130 uT) on every axis
+    pub fn coverage(&self) -> f32 {
+        if self.samples == 0 {
+            return 0.0;
+        }
+        let mut smallest = f32::MAX;
+        for axis in 0..3 {
+            let span = self.max[axis] - self.min[axis];
+            if span < smallest {
+                smallest = span;
+            }
+        }
+        smallest
+    }
+
+    // None until every axis has seen a non-zero span
+    pub fn compute(&self) -> Option<MagCalibration> {
+        if self.coverage() <= 0.0 {
+            return None;
+        }
+
+        let mut calibration = MagCalibration::default();
+        let radius: [f32; 3] = core::array::from_fn(|axis| (self.max[axis] - self.min[axis]) / 2.0);
+        calibration.hard_iron = core::array::from_fn(|axis| (self.max[axis] + self.min[axis]) / 2.0);
+
+        let average_radius = (radius[0] + radius[1] + radius[2]) / 3.0;
+        calibration.soft_iron = radius.map(|r| average_radius / r);
+        Some(calibration)
+    }
+}
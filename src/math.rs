@@ -0,0 +1,73 @@
+// Floating point type used by the crate's algorithms (calibration, fusion, SpO2, ...).
+// Drivers always report f32; algorithms run in `Float`, which is f32 by default for MCUs
+// and f64 when the `f64` feature is enabled (double-precision FPUs, host-side analysis).
+#[cfg(not(feature = "f64"))]
+pub type Float = f32;
+
+#[cfg(feature = "f64")]
+pub type Float = f64;
+
+// Math backend used by the algorithms. Enable exactly one of:
+//   `libm`      - accurate, larger code size
+//   `micromath` - fast approximations, small code size (f32 only)
+// If both are enabled `libm` is used.
+#[cfg(all(feature = "f64", feature = "micromath", not(feature = "libm")))]
+compile_error!("the `micromath` backend only supports f32; enable `libm` or disable `f64`");
+
+#[cfg(feature = "libm")]
+mod backend {
+    use super::Float;
+
+    #[cfg(not(feature = "f64"))]
+    pub use libm::{sqrtf as sqrt, sinf as sin, cosf as cos, atan2f as atan2, asinf as asin, powf, expf as exp};
+
+    #[cfg(feature = "f64")]
+    pub use libm::{sqrt, sin, cos, atan2, asin, pow as powf, exp};
+
+    pub fn abs(x: Float) -> Float {
+        if x < 0.0 { -x } else { x }
+    }
+}
+
+#[cfg(all(feature = "micromath", not(feature = "libm")))]
+mod backend {
+    use super::Float;
+    // Called through the trait so host test builds, where std's inherent f32 methods are in
+    // scope, still use micromath
+    use micromath::F32Ext;
+
+    pub fn sqrt(x: Float) -> Float {
+        F32Ext::sqrt(x)
+    }
+
+    pub fn sin(x: Float) -> Float {
+        F32Ext::sin(x)
+    }
+
+    pub fn cos(x: Float) -> Float {
+        F32Ext::cos(x)
+    }
+
+    pub fn atan2(y: Float, x: Float) -> Float {
+        F32Ext::atan2(y, x)
+    }
+
+    pub fn asin(x: Float) -> Float {
+        F32Ext::asin(x)
+    }
+
+    pub fn powf(x: Float, n: Float) -> Float {
+        F32Ext::powf(x, n)
+    }
+
+    pub fn exp(x: Float) -> Float {
+        F32Ext::exp(x)
+    }
+
+    pub fn abs(x: Float) -> Float {
+        if x < 0.0 { -x } else { x }
+    }
+}
+
+#[cfg(any(feature = "libm", feature = "micromath"))]
+pub use backend::*;
@@ -2,20 +2,27 @@
 use embedded_hal::i2c::I2c;
 
 #[cfg(feature = "max30102")]
-use crate::error::Error;
+use embedded_hal::delay::DelayNs;
+
+#[cfg(feature = "max30102")]
+use crate::error::{Access, Error};
+
+#[cfg(feature = "max30102")]
+use crate::device::{Device, DeviceInfo};
+
+#[cfg(feature = "max30102")]
+use crate::watchdog::{Recover, RecoveryAction};
 
 #[cfg(feature = "max30102")]
 mod registers {
     // Device Identification
-    pub const PART_ID: u8 = 0xFF;
+    pub const REV_ID: u8 = 0xFE;   // PART_ID (0xFF) is read in the same transfer
 
     // Status Registers
-    pub const INT_STATUS_1: u8 = 0x00;
-    pub const INT_STATUS_2: u8 = 0x01;
+    pub const INT_STATUS_1: u8 = 0x00;   // INT_STATUS_2 (0x01) is read in the same transfer
 
     // Interrupt enable registers
-    pub const INT_ENABLE_1: u8 = 0x02;
-    pub const INT_ENABLE_2: u8 = 0x03;
+    pub const INT_ENABLE_1: u8 = 0x02;   // INT_ENABLE_2 (0x03) is written in the same transfer
 
     // FIFO registers
     pub const FIFO_WR_PTR: u8 = 0x04;
@@ -31,6 +38,7 @@ mod registers {
     // LED Pulse amplitude registers
     pub const LED1_PA: u8 = 0x0C;
     pub const LED2_PA: u8 = 0x0D;
+    pub const LED3_PA: u8 = 0x0E;   // MAX30101 / MAX30105 green LED
     pub const PILOT_PA: u8 = 0x10;
 
     // Multi-LED Mode Configuration
@@ -52,10 +60,77 @@ use registers::*;
 #[cfg(feature = "max30102")]
 pub struct Max30102<I2C> {
     i2c: I2C,
-    address: u8
+    address: u8,
+    mode: OperationMode,      // Last mode written or read back, decides the FIFO sample layout
+    slots: [LedSlot; 4],      // Multi-LED slot configuration
+    variant: ChipVariant,
+    revision: Option<u8>,     // REV_ID, once read by `verify_identity()` or `device_info()`
+    next_index: u32,          // Sequence number of the next sample the chip writes
+    fifo_rollover: bool,      // FIFO_ROLLOVER_EN as last written, decides where dropped samples belong
+    pending_gap: Option<(u8, u8)>,   // Without rollover: samples still ahead of a gap, samples dropped there
+    errata: Errata,
+}
+
+// Workarounds for silicon revisions that deviate from the datasheet. They are looked up from
+// REV_ID when the chip is identified and can be overridden with `set_errata()` for parts
+// characterised in the field.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg(feature = "max30102")]
+pub struct Errata {
+    // DIE_TEMP_RDY does not get set reliably; `read_temperature()` then waits for TEMP_EN to
+    // self-clear instead, which every revision does at the end of a conversion
+    pub temperature_ready_unreliable: bool,
+}
+
+// Revisions with known deviations. Maxim publishes no revision-specific errata for the
+// MAX3010x, so every revision currently runs without workarounds; add entries here as they
+// are characterised.
+#[cfg(feature = "max30102")]
+const REVISION_ERRATA: &[(u8, Errata)] = &[];
+
+#[cfg(feature = "max30102")]
+impl Errata {
+    pub fn for_revision(revision: u8) -> Self {
+        REVISION_ERRATA
+            .iter()
+            .find(|(known, _)| *known == revision)
+            .map_or(Errata::default(), |&(_, errata)| errata)
+    }
+}
+
+// Parts sharing the MAX30102 register map. They all report PART_ID 0x15, so the variant cannot
+// be read from the chip and has to be given with `new_with_variant()`.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg(feature = "max30102")]
+pub enum ChipVariant {
+    #[default]
+    Max30102,   // Red + IR
+    Max30101,   // Red + IR + green
+    Max30105,   // Red + IR + green, particle sensing
+}
+
+#[cfg(feature = "max30102")]
+impl ChipVariant {
+    pub fn has_green_led(&self) -> bool {
+        matches!(self, ChipVariant::Max30101 | ChipVariant::Max30105)
+    }
+
+    pub fn device(&self) -> Device {
+        match self {
+            ChipVariant::Max30102 => Device::Max30102,
+            ChipVariant::Max30101 => Device::Max30101,
+            ChipVariant::Max30105 => Device::Max30105,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg(feature = "max30102")]
 pub enum OperationMode {
     HeartRate = 0x02,
@@ -64,6 +139,8 @@ pub enum OperationMode {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg(feature = "max30102")]
 pub enum SamplingRate {
     Rate50 = 0x00,
@@ -76,7 +153,25 @@ pub enum SamplingRate {
     Rate3200 = 0x07,
 }
 
+#[cfg(feature = "max30102")]
+impl SamplingRate {
+    pub fn hz(&self) -> u16 {
+        match self {
+            SamplingRate::Rate50 => 50,
+            SamplingRate::Rate100 => 100,
+            SamplingRate::Rate200 => 200,
+            SamplingRate::Rate400 => 400,
+            SamplingRate::Rate800 => 800,
+            SamplingRate::Rate1000 => 1000,
+            SamplingRate::Rate1600 => 1600,
+            SamplingRate::Rate3200 => 3200,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg(feature = "max30102")]
 pub enum LedPulseWidth {
     Width69us = 0x00,    // 15-bit resolution
@@ -86,6 +181,8 @@ pub enum LedPulseWidth {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg(feature = "max30102")]
 pub enum AdcRange {
     Range2048na = 0x00,   // LSB = 7.81 pA
@@ -95,6 +192,8 @@ pub enum AdcRange {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg(feature = "max30102")]
 pub enum SampleAveraging {
     NoAveraging = 0x00,
@@ -105,33 +204,628 @@ pub enum SampleAveraging {
     Average32 = 0x05,
 }
 
+#[cfg(feature = "max30102")]
+impl SampleAveraging {
+    // Conversions averaged into one FIFO sample
+    pub fn samples(&self) -> u8 {
+        1 << (*self as u8)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg(feature = "max30102")]
 pub enum InterruptSource {
     FifoAlmostFull = 0x80,    // Bit 7 of INT_ENABLE_1
     NewDataReady = 0x40,      // Bit 6 of INT_ENABLE_1
     AlcOverflow = 0x20,       // Bit 5 of INT_ENABLE_1
+    Proximity = 0x10,         // Bit 4 of INT_ENABLE_1
     PowerReady = 0x01,        // Bit 0 of INT_ENABLE_1
     TemperatureReady = 0x02,  // Bit 1 of INT_ENABLE_2
 }
 
+#[cfg(feature = "max30102")]
+impl InterruptSource {
+    // Bits to set in INT_ENABLE_1 and INT_ENABLE_2 for a set of sources
+    fn enable_masks(interrupts: &[InterruptSource]) -> [u8; 2] {
+        interrupts.iter().fold([0u8; 2], |[mask1, mask2], &interrupt| match interrupt {
+            InterruptSource::TemperatureReady => [mask1, mask2 | interrupt as u8],
+            _ => [mask1 | interrupt as u8, mask2],
+        })
+    }
+}
+
+// Flags of INT_STATUS_1 and INT_STATUS_2. Reading the status clears it on the chip, so this is
+// a one-shot snapshot of everything that was pending.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg(feature = "max30102")]
+pub struct InterruptStatus {
+    pub fifo_almost_full: bool,
+    pub new_data_ready: bool,
+    pub alc_overflow: bool,
+    pub proximity: bool,
+    pub power_ready: bool,
+    pub temperature_ready: bool,
+}
+
+#[cfg(feature = "max30102")]
+impl InterruptStatus {
+    pub fn from_registers(status1: u8, status2: u8) -> Self {
+        InterruptStatus {
+            fifo_almost_full: status1 & InterruptSource::FifoAlmostFull as u8 != 0,
+            new_data_ready: status1 & InterruptSource::NewDataReady as u8 != 0,
+            alc_overflow: status1 & InterruptSource::AlcOverflow as u8 != 0,
+            proximity: status1 & InterruptSource::Proximity as u8 != 0,
+            power_ready: status1 & InterruptSource::PowerReady as u8 != 0,
+            temperature_ready: status2 & InterruptSource::TemperatureReady as u8 != 0,
+        }
+    }
+
+    pub fn contains(&self, source: InterruptSource) -> bool {
+        match source {
+            InterruptSource::FifoAlmostFull => self.fifo_almost_full,
+            InterruptSource::NewDataReady => self.new_data_ready,
+            InterruptSource::AlcOverflow => self.alc_overflow,
+            InterruptSource::Proximity => self.proximity,
+            InterruptSource::PowerReady => self.power_ready,
+            InterruptSource::TemperatureReady => self.temperature_ready,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == InterruptStatus::default()
+    }
+}
+
+// Findings of `Max30102::diagnose()`, each flag one common reason for missing or flat data
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg(feature = "max30102")]
+pub struct Diagnosis {
+    pub part_id_mismatch: bool,    // PART_ID is not 0x15, wrong device or bus problem
+    pub shutdown: bool,            // SHDN set, nothing is sampled
+    pub invalid_mode: bool,        // MODE bits hold a reserved value
+    pub no_slots: bool,            // MultiLed mode with slot 1 disabled
+    pub dark_leds: [bool; 3],      // Red, IR, green LED used by the mode but at amplitude 0
+    pub timing_violation: bool,    // Sampling rate too high for the pulse width
+    pub fifo_overflowed: bool,     // Samples were dropped since the last read
+    pub fifo_stalled: bool,        // Sensor should be sampling but the FIFO did not advance
+}
+
+#[cfg(feature = "max30102")]
+impl Diagnosis {
+    pub fn is_healthy(&self) -> bool {
+        *self == Diagnosis::default()
+    }
+}
+
+// Ambient light overflow (ALC_OVF): the ambient light cancellation ran out of range, so the
+// ADC output contains ambient light on top of the LED signal. Reported by
+// `handle_ambient_overflow()` / `recover_ambient_overflow()` with the step that was taken.
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg(feature = "max30102")]
+pub enum AmbientOverflow {
+    RangeIncreased(AdcRange),   // ADC full scale raised one step
+    CurrentReduced,             // Already at the largest range, LED currents halved
+    Exhausted,                  // Nothing left to reduce, the sensor needs shielding from light
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg(feature = "max30102")]
 pub enum LedSlot {
+    #[default]
     None = 0x00, 
     Led1Red = 0x01, 
     Led2Ir = 0x02, 
-    Led3Green = 0x03,    // For MAX30101 only 
+    Led3Green = 0x03,    // MAX30101 / MAX30105 only
     PilotLed1 = 0x05,
     PilotLed2 = 0x06,
-    PilotLed3 = 0x07,   // For MAX30101 only
+    PilotLed3 = 0x07,    // MAX30101 / MAX30105 only
 }
 
-#[derive(Debug)]
+// One FIFO sample. Which channels are present depends on the operation mode: HeartRate mode
+// only drives the red LED, SpO2 mode red and IR, and MultiLed mode whatever the slots select.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg(feature = "max30102")]
 pub struct FifoSample {
-    pub red: u32,
-    pub ir: u32,
+    pub red: Option<u32>,
+    pub ir: Option<u32>,
+    pub green: Option<u32>,   // MAX30101 / MAX30105 in MultiLed mode
+    pub index: u32,           // Sequence number, see `Max30102::next_sample_index()`
+}
+
+// Complete sensor configuration for `initialize_with_config()`. The default matches
+// `initialize_sensor()`; override what is needed:
+//   Max30102Config::default().mode(OperationMode::HeartRate).sampling_rate(SamplingRate::Rate50)
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg(feature = "max30102")]
+pub struct Max30102Config {
+    pub mode: OperationMode,
+    pub adc_range: AdcRange,
+    pub sampling_rate: SamplingRate,
+    pub pulse_width: LedPulseWidth,
+    pub averaging: SampleAveraging,
+    pub fifo_rollover: bool,
+    pub fifo_almost_full_threshold: u8,   // 0-15 free slots left when FIFO_A_FULL fires
+    pub led_amplitudes: [u8; 3],          // Red, IR, green (green only on MAX30101/MAX30105)
+    pub multi_led_slots: [LedSlot; 4],    // Used in MultiLed mode
+    pub interrupts: [u8; 2],              // INT_ENABLE_1 / INT_ENABLE_2 masks built from InterruptSource values
+}
+
+#[cfg(feature = "max30102")]
+impl Default for Max30102Config {
+    fn default() -> Self {
+        Max30102Config {
+            mode: OperationMode::SpO2,
+            adc_range: AdcRange::Range4096na,
+            sampling_rate: SamplingRate::Rate100,
+            pulse_width: LedPulseWidth::Width411us,
+            averaging: SampleAveraging::Average4,
+            fifo_rollover: true,
+            fifo_almost_full_threshold: 15,
+            led_amplitudes: [0x1F, 0x1F, 0x00],
+            multi_led_slots: [LedSlot::None; 4],
+            interrupts: InterruptSource::enable_masks(&[InterruptSource::FifoAlmostFull, InterruptSource::NewDataReady]),
+        }
+    }
+}
+
+#[cfg(feature = "max30102")]
+impl Max30102Config {
+    pub fn mode(mut self, mode: OperationMode) -> Self {
+        self.mode = mode;
+        self
+    }
+
+    pub fn adc_range(mut self, range: AdcRange) -> Self {
+        self.adc_range = range;
+        self
+    }
+
+    pub fn sampling_rate(mut self, rate: SamplingRate) -> Self {
+        self.sampling_rate = rate;
+        self
+    }
+
+    pub fn pulse_width(mut self, width: LedPulseWidth) -> Self {
+        self.pulse_width = width;
+        self
+    }
+
+    pub fn averaging(mut self, averaging: SampleAveraging) -> Self {
+        self.averaging = averaging;
+        self
+    }
+
+    pub fn fifo_rollover(mut self, enable: bool) -> Self {
+        self.fifo_rollover = enable;
+        self
+    }
+
+    pub fn fifo_almost_full_threshold(mut self, threshold: u8) -> Self {
+        self.fifo_almost_full_threshold = threshold;
+        self
+    }
+
+    // `led` is 1 (red), 2 (IR) or 3 (green); other values are ignored
+    pub fn led_amplitude(mut self, led: u8, amplitude: u8) -> Self {
+        if let Some(slot) = self.led_amplitudes.get_mut((led as usize).wrapping_sub(1)) {
+            *slot = amplitude;
+        }
+        self
+    }
+
+    pub fn multi_led_slots(mut self, slots: [LedSlot; 4]) -> Self {
+        self.multi_led_slots = slots;
+        self
+    }
+
+    pub fn enable_interrupt(mut self, interrupt: InterruptSource) -> Self {
+        let [mask1, mask2] = InterruptSource::enable_masks(&[interrupt]);
+        self.interrupts = [self.interrupts[0] | mask1, self.interrupts[1] | mask2];
+        self
+    }
+
+    pub fn disable_interrupt(mut self, interrupt: InterruptSource) -> Self {
+        let [mask1, mask2] = InterruptSource::enable_masks(&[interrupt]);
+        self.interrupts = [self.interrupts[0] & !mask1, self.interrupts[1] & !mask2];
+        self
+    }
+
+    // FIFO_CONFIG, MODE_CONFIG and SPO2_CONFIG register values
+    fn register_values(&self) -> [u8; 3] {
+        [
+            ((self.averaging as u8) << 5) | ((self.fifo_rollover as u8) << 4) | self.fifo_almost_full_threshold,
+            self.mode as u8,
+            ((self.adc_range as u8) << 5) | ((self.sampling_rate as u8) << 2) | self.pulse_width as u8,
+        ]
+    }
+}
+
+// One FIFO sample with every channel tagged by the LED slot that produced it, in FIFO order.
+// Covers MultiLed mode with up to four slots, including the green LED of the MAX30101.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg(feature = "max30102")]
+pub struct MultiLedSample {
+    pub slots: [LedSlot; 4],
+    pub values: [u32; 4],
+    pub count: usize,   // Number of valid entries in `slots` / `values`
+    pub index: u32,     // Sequence number, as for `FifoSample`
+}
+
+#[cfg(feature = "max30102")]
+impl MultiLedSample {
+    // Value of the first channel driven by `slot`
+    pub fn get(&self, slot: LedSlot) -> Option<u32> {
+        self.iter().find(|&(channel, _)| channel == slot).map(|(_, value)| value)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (LedSlot, u32)> + '_ {
+        self.slots[..self.count].iter().copied().zip(self.values[..self.count].iter().copied())
+    }
+}
+
+// Red, IR and green channels of a multi-LED sample; pilot LED slots count as their LED
+#[cfg(feature = "max30102")]
+impl From<MultiLedSample> for FifoSample {
+    fn from(sample: MultiLedSample) -> Self {
+        let mut fifo_sample = FifoSample { index: sample.index, ..FifoSample::default() };
+        for (slot, value) in sample.iter() {
+            match slot {
+                LedSlot::Led1Red | LedSlot::PilotLed1 => fifo_sample.red = Some(value),
+                LedSlot::Led2Ir | LedSlot::PilotLed2 => fifo_sample.ir = Some(value),
+                LedSlot::Led3Green | LedSlot::PilotLed3 => fifo_sample.green = Some(value),
+                LedSlot::None => {}
+            }
+        }
+        fifo_sample
+    }
+}
+
+// Registers 0x00-0x30 plus REV_ID and PART_ID
+#[cfg(feature = "max30102")]
+const REGISTER_DUMP_SIZE: usize = 0x33;
+
+// Samples read per I2C transfer by `FifoSamples`
+#[cfg(feature = "max30102")]
+const SAMPLES_CHUNK: usize = 8;
+
+// FIFO pointers as read in one transfer
+#[cfg(feature = "max30102")]
+struct FifoState {
+    available: u8,      // Samples waiting to be read
+    overflow: u8,       // Samples dropped because the FIFO was full, saturates at 31
+    read_pointer: u8,
+}
+
+// Iterator returned by `Max30102::samples()`. Samples are fetched from the FIFO in chunks and
+// handed out one at a time; when a chunk is used up the FIFO is checked again, so the iterator
+// keeps going while the sensor produces data and ends once the FIFO is empty. After an I2C
+// error it yields that error and then ends.
+#[cfg(feature = "max30102")]
+pub struct FifoSamples<'a, I2C> {
+    sensor: &'a mut Max30102<I2C>,
+    buffer: [FifoSample; SAMPLES_CHUNK],
+    len: usize,
+    position: usize,
+    dropped: u32,
+    done: bool,
+}
+
+#[cfg(feature = "max30102")]
+impl<I2C> FifoSamples<'_, I2C> {
+    // Samples the chip dropped because the FIFO was full, summed over every refill so far
+    pub fn dropped(&self) -> u32 {
+        self.dropped
+    }
+}
+
+#[cfg(feature = "max30102")]
+impl<I2C, E> Iterator for FifoSamples<'_, I2C>
+where
+    I2C: I2c<Error = E>,
+{
+    type Item = Result<FifoSample, Error<E>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        if self.position == self.len {
+            match self.sensor.read_fifo_batch_with_overflow(&mut self.buffer) {
+                Ok((0, _)) => {
+                    self.done = true;
+                    return None;
+                }
+                Ok((count, overflow)) => {
+                    self.len = count;
+                    self.position = 0;
+                    self.dropped += overflow as u32;
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+
+        let sample = self.buffer[self.position];
+        self.position += 1;
+        Some(Ok(sample))
+    }
+}
+
+// Finger / on-skin detection from the DC level of the IR channel (red in HeartRate mode).
+// Presence is declared above `on_threshold` and withdrawn below `off_threshold`; the gap
+// between them is the hysteresis. A change is only reported after `debounce` consecutive
+// samples agree, so a single motion spike does not toggle the state.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg(feature = "max30102")]
+pub struct FingerDetector {
+    on_threshold: u32,
+    off_threshold: u32,
+    debounce: u8,
+    present: bool,
+    pending: u8,
+}
+
+#[cfg(feature = "max30102")]
+impl Default for FingerDetector {
+    // Suits `initialize_sensor()` settings; ambient light alone stays well below 30000 counts
+    fn default() -> Self {
+        Self::new(50_000, 30_000, 5)
+    }
+}
+
+#[cfg(feature = "max30102")]
+impl FingerDetector {
+    pub fn new(on_threshold: u32, off_threshold: u32, debounce: u8) -> Self {
+        FingerDetector {
+            on_threshold: on_threshold.max(off_threshold),
+            off_threshold: off_threshold.min(on_threshold),
+            debounce: debounce.max(1),
+            present: false,
+            pending: 0,
+        }
+    }
+
+    // Feed one sample and return the debounced presence state. Samples without an IR or red
+    // channel leave the state unchanged.
+    pub fn update(&mut self, sample: &FifoSample) -> bool {
+        let Some(level) = sample.ir.or(sample.red) else {
+            return self.present;
+        };
+
+        let toggles = if self.present { level < self.off_threshold } else { level > self.on_threshold };
+        if !toggles {
+            self.pending = 0;
+            return self.present;
+        }
+
+        self.pending += 1;
+        if self.pending >= self.debounce {
+            self.present = !self.present;
+            self.pending = 0;
+        }
+        self.present
+    }
+
+    pub fn is_finger_present(&self) -> bool {
+        self.present
+    }
+
+    pub fn reset(&mut self) {
+        self.present = false;
+        self.pending = 0;
+    }
+
+    // Override the state, e.g. when the proximity function has already confirmed a finger
+    pub fn set_present(&mut self, present: bool) {
+        self.present = present;
+        self.pending = 0;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg(feature = "max30102")]
+pub enum ProximityState {
+    Searching,   // Only the pilot LED runs, waiting for the proximity threshold
+    Measuring,   // Proximity fired, the chip runs the configured SpO2/HR mode
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg(feature = "max30102")]
+pub enum ProximityEvent {
+    Detected,
+    Removed,
+}
+
+// Proximity-gated measurement. With the proximity interrupt enabled the chip pulses only the
+// pilot LED until the IR reading crosses PROX_INT_THRESH, then switches to the configured mode
+// on its own. Returning to pilot-only operation is up to the host, which this gate does when
+// the finger detector reports the finger gone.
+#[cfg(feature = "max30102")]
+pub struct ProximityGate {
+    state: ProximityState,
+    finger: FingerDetector,
+}
+
+#[cfg(feature = "max30102")]
+impl ProximityGate {
+    // Call after `enable_proximity_mode()`
+    pub fn new(finger: FingerDetector) -> Self {
+        ProximityGate { state: ProximityState::Searching, finger }
+    }
+
+    pub fn state(&self) -> ProximityState {
+        self.state
+    }
+
+    // While searching, check whether proximity fired (e.g. from the INT pin handler). Reading
+    // INT_STATUS_1 clears the other flags in that register as well.
+    pub fn poll<I2C, E>(&mut self, sensor: &mut Max30102<I2C>) -> Result<Option<ProximityEvent>, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        if self.state != ProximityState::Searching || !sensor.is_proximity_triggered()? {
+            return Ok(None);
+        }
+
+        self.state = ProximityState::Measuring;
+        self.finger.set_present(true);
+        Ok(Some(ProximityEvent::Detected))
+    }
+
+    // While measuring, feed every sample read from the FIFO. Once the finger is gone the chip
+    // is put back into pilot-only proximity search.
+    pub fn update<I2C, E>(&mut self, sensor: &mut Max30102<I2C>, sample: &FifoSample) -> Result<Option<ProximityEvent>, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        if self.state != ProximityState::Measuring || self.finger.update(sample) {
+            return Ok(None);
+        }
+
+        sensor.rearm_proximity()?;
+        self.state = ProximityState::Searching;
+        Ok(Some(ProximityEvent::Removed))
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg(feature = "max30102")]
+pub enum WearEvent {
+    Donned,   // Sensor confirmed on skin
+    Doffed,   // Sensor taken off, back to pilot-only proximity search
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg(feature = "max30102")]
+pub enum WearState {
+    Searching,    // Pilot LED only, waiting for the proximity interrupt
+    Confirming,   // Proximity fired, checking the IR level before reporting Donned
+    Worn,
+}
+
+// On-body detection for wearables, combining the three signals that are each unreliable alone:
+// the proximity interrupt wakes the measurement at low power, the debounced IR DC level of
+// `FingerDetector` confirms actual skin contact (a sleeve or table edge also trips proximity),
+// and the same detector reports removal. Call `poll_events()` periodically after
+// `enable_proximity_mode()`; vitals collection and LED power can follow the reported events.
+#[cfg(feature = "max30102")]
+pub struct WearDetector {
+    state: WearState,
+    finger: FingerDetector,
+    confirm_limit: u16,
+    confirm_samples: u16,
+}
+
+#[cfg(feature = "max30102")]
+impl WearDetector {
+    // Proximity triggers not confirmed within this many samples count as false alarms
+    pub const DEFAULT_CONFIRM_SAMPLES: u16 = 100;
+
+    pub fn new(finger: FingerDetector) -> Self {
+        WearDetector {
+            state: WearState::Searching,
+            finger,
+            confirm_limit: Self::DEFAULT_CONFIRM_SAMPLES,
+            confirm_samples: 0,
+        }
+    }
+
+    pub fn with_confirm_samples(mut self, samples: u16) -> Self {
+        self.confirm_limit = samples.max(1);
+        self
+    }
+
+    pub fn state(&self) -> WearState {
+        self.state
+    }
+
+    pub fn is_worn(&self) -> bool {
+        self.state == WearState::Worn
+    }
+
+    // Check for a wear change. Drains the FIFO outside the Searching state; while worn every
+    // sample is handed to `on_sample`, so vitals processing keeps getting the data. Reading the
+    // proximity flag while searching clears the other INT_STATUS_1 flags as well.
+    pub fn poll_events<I2C, E>(
+        &mut self,
+        sensor: &mut Max30102<I2C>,
+        mut on_sample: impl FnMut(&FifoSample),
+    ) -> Result<Option<WearEvent>, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        if self.state == WearState::Searching {
+            if !sensor.is_proximity_triggered()? {
+                return Ok(None);
+            }
+            self.state = WearState::Confirming;
+            self.confirm_samples = 0;
+            self.finger.reset();
+        }
+
+        let mut event = None;
+        let mut batch = [FifoSample::default(); 32];
+        loop {
+            let count = sensor.read_fifo_batch(&mut batch)?;
+            for sample in &batch[..count] {
+                let present = self.finger.update(sample);
+                match self.state {
+                    WearState::Confirming if present => {
+                        self.state = WearState::Worn;
+                        event = Some(WearEvent::Donned);
+                        on_sample(sample);
+                    }
+                    WearState::Confirming => {
+                        self.confirm_samples += 1;
+                        if self.confirm_samples >= self.confirm_limit {
+                            sensor.rearm_proximity()?;
+                            self.state = WearState::Searching;
+                            return Ok(None);
+                        }
+                    }
+                    // A removal in the same batch as Donned is reported on the next poll
+                    WearState::Worn if !present && event.is_none() => {
+                        sensor.rearm_proximity()?;
+                        self.state = WearState::Searching;
+                        return Ok(Some(WearEvent::Doffed));
+                    }
+                    WearState::Worn => on_sample(sample),
+                    WearState::Searching => {}
+                }
+            }
+            if count < batch.len() {
+                break;
+            }
+        }
+
+        if self.state == WearState::Worn && event.is_none() && !self.finger.is_finger_present() {
+            sensor.rearm_proximity()?;
+            self.state = WearState::Searching;
+            return Ok(Some(WearEvent::Doffed));
+        }
+        Ok(event)
+    }
 }
 
 #[cfg(feature = "max30102")]
@@ -148,8 +842,30 @@ where
     // Expected Part ID for MAX30102
     pub const EXPECTED_PART_ID: u8 = 0x15;
 
+    // Registers 0x00-0x30 followed by REV_ID and PART_ID, as captured by `dump_registers()`
+    pub const REGISTER_DUMP_SIZE: usize = REGISTER_DUMP_SIZE;
+
     pub fn new(i2c: I2C, address: u8) -> Self {
-        Max30102 { i2c, address }
+        Max30102 {
+            i2c,
+            address,
+            mode: OperationMode::SpO2,
+            slots: [LedSlot::None; 4],
+            variant: ChipVariant::Max30102,
+            revision: None,
+            next_index: 0,
+            fifo_rollover: false,   // Power-on default
+            pending_gap: None,
+            errata: Errata::default(),
+        }
+    }
+
+    pub fn new_with_variant(i2c: I2C, address: u8, variant: ChipVariant) -> Self {
+        Max30102 { variant, ..Self::new(i2c, address) }
+    }
+
+    pub fn variant(&self) -> ChipVariant {
+        self.variant
     }
 
     // Create a new instance with default I2C address
@@ -157,92 +873,198 @@ where
         Self::new(i2c, Self::DEFAULT_ADDRESS)
     }
 
+    // Consume the driver and return the I2C bus. The chip keeps sampling into its FIFO.
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    // Also records the silicon revision and applies its errata
     pub fn verify_identity(&mut self) -> Result<(), Error<E>> {
-        let mut buffer = [0u8];
-        self.i2c.write_read(self.address, &[PART_ID], &mut buffer)?;
+        self.read_identity().map(|_| ())
+    }
+
+    pub fn device_info(&mut self) -> Result<DeviceInfo, Error<E>> {
+        let revision = self.read_identity()?;
+
+        Ok(DeviceInfo {
+            device: self.variant.device(),
+            part_id: Self::EXPECTED_PART_ID,
+            revision: Some(revision),
+            address: self.address,
+        })
+    }
+
+    // Silicon revision (REV_ID), None until the chip has been identified
+    pub fn revision(&self) -> Option<u8> {
+        self.revision
+    }
+
+    pub fn errata(&self) -> Errata {
+        self.errata
+    }
+
+    pub fn set_errata(&mut self, errata: Errata) {
+        self.errata = errata;
+    }
+
+    // Check PART_ID and return REV_ID
+    fn read_identity(&mut self) -> Result<u8, Error<E>> {
+        // REV_ID and PART_ID are adjacent, read both in one transaction
+        let mut buffer = [0u8; 2];
+        self.i2c.write_read(self.address, &[REV_ID], &mut buffer).map_err(Error::read(REV_ID))?;
+
+        if buffer[1] != Self::EXPECTED_PART_ID {
+            return Err(Error::NotDetected);
+        }
 
-        match buffer[0] {
-            Self::EXPECTED_PART_ID => Ok(()),
-            _ => Err(Error::NotDetected)
+        if self.revision != Some(buffer[0]) {
+            self.revision = Some(buffer[0]);
+            self.errata = Errata::for_revision(buffer[0]);
         }
+        Ok(buffer[0])
     }
 
     pub fn reset(&mut self) -> Result<(), Error<E>> {
-        self.i2c.write(self.address, &[MODE_CONFIG, 0x40])?;
+        self.i2c.write(self.address, &[MODE_CONFIG, 0x40]).map_err(Error::write(MODE_CONFIG))?;
+        self.fifo_rollover = false;
+        self.pending_gap = None;
         Ok(())
     }
 
     pub fn shutdown(&mut self) -> Result<(), Error<E>> {
         // Set shutdown bit (Bit 7) in MODE_CONFIG register
         let mut current_config = [0u8];
-        self.i2c.write_read(self.address, &[MODE_CONFIG], &mut current_config)?;
+        self.i2c.write_read(self.address, &[MODE_CONFIG], &mut current_config).map_err(Error::read(MODE_CONFIG))?;
         let new_config = current_config[0] | 0x80;
-        self.i2c.write(self.address, &[MODE_CONFIG, new_config])?;
+        self.i2c.write(self.address, &[MODE_CONFIG, new_config]).map_err(Error::write(MODE_CONFIG))?;
         Ok(())
     }
 
     pub fn wakeup(&mut self) -> Result<(), Error<E>> {
         // Clear shutdown bit in MODE_CONFIG register
         let mut current_config = [0u8];
-        self.i2c.write_read(self.address, &[MODE_CONFIG], &mut current_config)?;
+        self.i2c.write_read(self.address, &[MODE_CONFIG], &mut current_config).map_err(Error::read(MODE_CONFIG))?;
         let new_config = current_config[0] & 0x7F;  // Fixed: should be & not |
-        self.i2c.write(self.address, &[MODE_CONFIG, new_config])?;
+        self.i2c.write(self.address, &[MODE_CONFIG, new_config]).map_err(Error::write(MODE_CONFIG))?;
         Ok(())
     }
 
     pub fn set_operation_mode(&mut self, mode: OperationMode) -> Result<(), Error<E>> {
         let mut current_config = [0u8];
-        self.i2c.write_read(self.address, &[MODE_CONFIG], &mut current_config)?;
+        self.i2c.write_read(self.address, &[MODE_CONFIG], &mut current_config).map_err(Error::read(MODE_CONFIG))?;
+
+        // A mode with more LEDs per sample can make the current rate / pulse width illegal
+        let spo2_config = self.read_reg(SPO2_CONFIG)?;
+        check_timing(Self::leds_per_sample(mode, &self.slots), spo2_config)?;
 
         // Clear mode bits and set new mode (preserve other bits)
         let new_config = (current_config[0] & 0xF8) | (mode as u8);
-        self.i2c.write(self.address, &[MODE_CONFIG, new_config])?;
+        self.i2c.write(self.address, &[MODE_CONFIG, new_config]).map_err(Error::write(MODE_CONFIG))?;
+        self.mode = mode;
         Ok(())
     }
 
+    // Mode the FIFO reads currently assume
+    pub fn operation_mode(&self) -> OperationMode {
+        self.mode
+    }
+
+    fn leds_per_sample(mode: OperationMode, slots: &[LedSlot; 4]) -> usize {
+        match mode {
+            OperationMode::HeartRate => 1,
+            OperationMode::SpO2 => 2,
+            OperationMode::MultiLed => slots.iter().take_while(|&&slot| slot != LedSlot::None).count(),
+        }
+    }
+
+    // LED of each 3-byte word in a FIFO sample, in FIFO order. In MultiLed mode the chip stops
+    // at the first disabled slot.
+    fn sample_layout(&self) -> ([LedSlot; 4], usize) {
+        match self.mode {
+            OperationMode::HeartRate => ([LedSlot::Led1Red, LedSlot::None, LedSlot::None, LedSlot::None], 1),
+            OperationMode::SpO2 => ([LedSlot::Led1Red, LedSlot::Led2Ir, LedSlot::None, LedSlot::None], 2),
+            OperationMode::MultiLed => {
+                let active = self.slots.iter().take_while(|&&slot| slot != LedSlot::None).count();
+                (self.slots, active)
+            }
+        }
+    }
+
+    // Bytes per FIFO sample in the current mode
+    pub fn sample_size(&self) -> usize {
+        self.sample_layout().1 * 3
+    }
+
+    // Slot configuration last written with `set_multi_led_slots()`
+    pub fn led_slots(&self) -> [LedSlot; 4] {
+        self.slots
+    }
+
+    fn decode_multi_led(&self, data: &[u8], index: u32) -> MultiLedSample {
+        let (slots, active) = self.sample_layout();
+        let mut sample = MultiLedSample { slots, count: active, index, ..MultiLedSample::default() };
+
+        for (value, word) in sample.values[..active].iter_mut().zip(data.chunks_exact(3)) {
+            // 18-bit left-justified value
+            *value = (((word[0] as u32) << 16) | ((word[1] as u32) << 8) | word[2] as u32) & 0x03FFFF;
+        }
+        sample
+    }
+
+    fn decode_sample(&self, data: &[u8], index: u32) -> FifoSample {
+        self.decode_multi_led(data, index).into()
+    }
+
     pub fn set_adc_range(&mut self, range: AdcRange) -> Result<(), Error<E>> {
         let mut current_config = [0u8];
-        self.i2c.write_read(self.address, &[SPO2_CONFIG], &mut current_config)?;
+        self.i2c.write_read(self.address, &[SPO2_CONFIG], &mut current_config).map_err(Error::read(SPO2_CONFIG))?;
 
         // Clear ADC bits (Bits 6:5) and set new Range 
         let new_config = (current_config[0] & 0x9F) | ((range as u8) << 5);
-        self.i2c.write(self.address, &[SPO2_CONFIG, new_config])?;
+        self.i2c.write(self.address, &[SPO2_CONFIG, new_config]).map_err(Error::write(SPO2_CONFIG))?;
         Ok(())
     }
 
     pub fn set_sampling_rate(&mut self, rate: SamplingRate) -> Result<(), Error<E>> {
         let mut current_config = [0u8];
-        self.i2c.write_read(self.address, &[SPO2_CONFIG], &mut current_config)?;
+        self.i2c.write_read(self.address, &[SPO2_CONFIG], &mut current_config).map_err(Error::read(SPO2_CONFIG))?;
 
         // Clear sampling rate bits (Bits 4:2) and set new Rate
         let new_config = (current_config[0] & 0xE3) | ((rate as u8) << 2);
-        self.i2c.write(self.address, &[SPO2_CONFIG, new_config])?;
+        check_timing(self.sample_layout().1, new_config)?;
+        self.i2c.write(self.address, &[SPO2_CONFIG, new_config]).map_err(Error::write(SPO2_CONFIG))?;
         Ok(())
     }
 
     pub fn set_pulse_width(&mut self, width: LedPulseWidth) -> Result<(), Error<E>> {
         let mut current_config = [0u8];
-        self.i2c.write_read(self.address, &[SPO2_CONFIG], &mut current_config)?;
+        self.i2c.write_read(self.address, &[SPO2_CONFIG], &mut current_config).map_err(Error::read(SPO2_CONFIG))?;
 
-        // Clear pulse width bits (Bits 1:0) and set new width
+        // Clear pulse width bits (Bits 1:0) and set new width. When moving to a longer pulse at
+        // a high rate, lower the rate first.
         let new_config = (current_config[0] & 0xFC) | (width as u8);
-        self.i2c.write(self.address, &[SPO2_CONFIG, new_config])?;
+        check_timing(self.sample_layout().1, new_config)?;
+        self.i2c.write(self.address, &[SPO2_CONFIG, new_config]).map_err(Error::write(SPO2_CONFIG))?;
         Ok(())
     }
 
     pub fn set_sample_averaging(&mut self, averaging: SampleAveraging) -> Result<(), Error<E>> {
         let mut current_config = [0u8];
-        self.i2c.write_read(self.address, &[FIFO_CONFIG], &mut current_config)?;
+        self.i2c.write_read(self.address, &[FIFO_CONFIG], &mut current_config).map_err(Error::read(FIFO_CONFIG))?;
 
         // Clear Sample Averaging bits (bits 7:5) and set new averaging
         let new_config = (current_config[0] & 0x1F) | ((averaging as u8) << 5);
-        self.i2c.write(self.address, &[FIFO_CONFIG, new_config])?;
+        self.i2c.write(self.address, &[FIFO_CONFIG, new_config]).map_err(Error::write(FIFO_CONFIG))?;
         Ok(())
     }
 
     pub fn enable_fifo_rollover(&mut self, enable: bool) -> Result<(), Error<E>> {
         let mut current_config = [0u8];
-        self.i2c.write_read(self.address, &[FIFO_CONFIG], &mut current_config)?;
+        self.i2c.write_read(self.address, &[FIFO_CONFIG], &mut current_config).map_err(Error::read(FIFO_CONFIG))?;
 
         let new_config = if enable {
             current_config[0] | 0x10    // Set bit 4
@@ -250,7 +1072,8 @@ where
             current_config[0] & 0xEF    // Clear Bit 4
         };
 
-        self.i2c.write(self.address, &[FIFO_CONFIG, new_config])?;
+        self.i2c.write(self.address, &[FIFO_CONFIG, new_config]).map_err(Error::write(FIFO_CONFIG))?;
+        self.fifo_rollover = enable;
         Ok(())
     }
 
@@ -260,11 +1083,11 @@ where
         }
 
         let mut current_config = [0u8];
-        self.i2c.write_read(self.address, &[FIFO_CONFIG], &mut current_config)?;
+        self.i2c.write_read(self.address, &[FIFO_CONFIG], &mut current_config).map_err(Error::read(FIFO_CONFIG))?;
 
         // Clear FIFO_A_FULL bits (bits 3:0) and set new threshold
         let new_config = (current_config[0] & 0xF0) | threshold;
-        self.i2c.write(self.address, &[FIFO_CONFIG, new_config])?;
+        self.i2c.write(self.address, &[FIFO_CONFIG, new_config]).map_err(Error::write(FIFO_CONFIG))?;
         Ok(())
     }
 
@@ -272,148 +1095,384 @@ where
         let register = match led {
             1 => LED1_PA,
             2 => LED2_PA,
+            3 if self.variant.has_green_led() => LED3_PA,
             _ => return Err(Error::ConfigError),
         };
 
-        self.i2c.write(self.address, &[register, amplitude])?;
+        self.i2c.write(self.address, &[register, amplitude]).map_err(Error::write(register))?;
         Ok(())
     }
 
+    // LED drive current in mA, 0.2 mA per amplitude step up to 51 mA. Returns the current
+    // actually programmed after rounding to the nearest step.
+    pub fn set_led_current_ma(&mut self, led: u8, milliamps: f32) -> Result<f32, Error<E>> {
+        if !(0.0..=51.0).contains(&milliamps) {
+            return Err(Error::ConfigError);
+        }
+        let amplitude = (milliamps * 5.0 + 0.5) as u8;
+        self.set_led_pulse_amplitude(led, amplitude)?;
+        Ok(amplitude as f32 * 0.2)
+    }
+
+    pub fn get_led_current_ma(&mut self, led: u8) -> Result<f32, Error<E>> {
+        Ok(self.get_led_pulse_amplitude(led)? as f32 * 0.2)
+    }
+
+    // Integer variant of `set_led_current_ma()` in microamps, 200 uA per step
+    #[cfg_attr(docsrs, doc(cfg(feature = "fixed-point")))]
+    #[cfg(feature = "fixed-point")]
+    pub fn set_led_current_ua(&mut self, led: u8, microamps: u32) -> Result<u32, Error<E>> {
+        if microamps > 51_000 {
+            return Err(Error::ConfigError);
+        }
+        let amplitude = ((microamps + 100) / 200) as u8;
+        self.set_led_pulse_amplitude(led, amplitude)?;
+        Ok(amplitude as u32 * 200)
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "fixed-point")))]
+    #[cfg(feature = "fixed-point")]
+    pub fn get_led_current_ua(&mut self, led: u8) -> Result<u32, Error<E>> {
+        Ok(self.get_led_pulse_amplitude(led)? as u32 * 200)
+    }
+
     pub fn set_pilot_led_amplitude(&mut self, amplitude: u8) -> Result<(), Error<E>> {
-        self.i2c.write(self.address, &[PILOT_PA, amplitude])?;
+        self.i2c.write(self.address, &[PILOT_PA, amplitude]).map_err(Error::write(PILOT_PA))?;
         Ok(())
     }
 
     pub fn enable_interrupt(&mut self, interrupt: InterruptSource) -> Result<(), Error<E>> {
-        let (register, mask) = match interrupt {
-            InterruptSource::FifoAlmostFull => (INT_ENABLE_1, 0x80),
-            InterruptSource::NewDataReady => (INT_ENABLE_1, 0x40),
-            InterruptSource::AlcOverflow => (INT_ENABLE_1, 0x20),
-            InterruptSource::PowerReady => (INT_ENABLE_1, 0x01),
-            InterruptSource::TemperatureReady => (INT_ENABLE_2, 0x02),
-        };
+        self.enable_interrupts(&[interrupt])
+    }
 
-        let mut current_config = [0u8];
-        self.i2c.write_read(self.address, &[register], &mut current_config)?;
+    pub fn disable_interrupt(&mut self, interrupt: InterruptSource) -> Result<(), Error<E>> {
+        self.disable_interrupts(&[interrupt])
+    }
 
-        let new_config = current_config[0] | mask;
-        self.i2c.write(self.address, &[register, new_config])?;
+    // Enable several sources with one read and one write of INT_ENABLE_1/INT_ENABLE_2
+    pub fn enable_interrupts(&mut self, interrupts: &[InterruptSource]) -> Result<(), Error<E>> {
+        let [mask1, mask2] = InterruptSource::enable_masks(interrupts);
+        let [enable1, enable2] = self.read_interrupt_enables()?;
+        self.i2c.write(self.address, &[INT_ENABLE_1, enable1 | mask1, enable2 | mask2]).map_err(Error::write(INT_ENABLE_1))?;
         Ok(())
     }
 
-    pub fn disable_interrupt(&mut self, interrupt: InterruptSource) -> Result<(), Error<E>> {
-        let (register, mask) = match interrupt {
-            InterruptSource::FifoAlmostFull => (INT_ENABLE_1, 0x80),
-            InterruptSource::NewDataReady => (INT_ENABLE_1, 0x40),
-            InterruptSource::AlcOverflow => (INT_ENABLE_1, 0x20),
-            InterruptSource::PowerReady => (INT_ENABLE_1, 0x01),
-            InterruptSource::TemperatureReady => (INT_ENABLE_2, 0x02),
-        };
+    pub fn disable_interrupts(&mut self, interrupts: &[InterruptSource]) -> Result<(), Error<E>> {
+        let [mask1, mask2] = InterruptSource::enable_masks(interrupts);
+        let [enable1, enable2] = self.read_interrupt_enables()?;
+        self.i2c.write(self.address, &[INT_ENABLE_1, enable1 & !mask1, enable2 & !mask2]).map_err(Error::write(INT_ENABLE_1))?;
+        Ok(())
+    }
 
-        let mut current_config = [0u8];
-        self.i2c.write_read(self.address, &[register], &mut current_config)?;
+    fn read_interrupt_enables(&mut self) -> Result<[u8; 2], Error<E>> {
+        // INT_ENABLE_1 and INT_ENABLE_2 are adjacent
+        let mut enables = [0u8; 2];
+        self.i2c.write_read(self.address, &[INT_ENABLE_1], &mut enables).map_err(Error::read(INT_ENABLE_1))?;
+        Ok(enables)
+    }
 
-        let new_config = current_config[0] & !mask;
-        self.i2c.write(self.address, &[register, new_config])?;
-        Ok(())
+    // Reading the status registers clears every flag they report
+    pub fn read_interrupt_status(&mut self) -> Result<InterruptStatus, Error<E>> {
+        // INT_STATUS_1 and INT_STATUS_2 are adjacent
+        let mut status = [0u8; 2];
+        self.i2c.write_read(self.address, &[INT_STATUS_1], &mut status).map_err(Error::read(INT_STATUS_1))?;
+        Ok(InterruptStatus::from_registers(status[0], status[1]))
     }
 
-    pub fn read_interrupt_status(&mut self) -> Result<(u8, u8), Error<E>> {
-        let mut status1 = [0u8];
-        let mut status2 = [0u8];
+    // Recover from an ambient light overflow reported in `status`, e.g. the result of
+    // `read_interrupt_status()` with the AlcOverflow interrupt enabled. Returns None when no
+    // overflow was flagged.
+    pub fn handle_ambient_overflow(&mut self, status: &InterruptStatus) -> Result<Option<AmbientOverflow>, Error<E>> {
+        if !status.alc_overflow {
+            return Ok(None);
+        }
+        self.recover_ambient_overflow().map(Some)
+    }
 
-        self.i2c.write_read(self.address, &[INT_STATUS_1], &mut status1)?;
-        self.i2c.write_read(self.address, &[INT_STATUS_2], &mut status2)?;
+    // Desensitise one step: raise the ADC full scale, or once at 16384 nA halve the LED
+    // currents. The FIFO is cleared either way, since the samples in it were taken with ambient
+    // light leaking into the ADC. Call again if the overflow persists.
+    pub fn recover_ambient_overflow(&mut self) -> Result<AmbientOverflow, Error<E>> {
+        let next_range = match self.get_adc_range()? {
+            AdcRange::Range2048na => Some(AdcRange::Range4096na),
+            AdcRange::Range4096na => Some(AdcRange::Range8192na),
+            AdcRange::Range8192na => Some(AdcRange::Range16384na),
+            AdcRange::Range16384na => None,
+        };
 
-        Ok((status1[0], status2[0]))
+        let action = match next_range {
+            Some(range) => {
+                self.set_adc_range(range)?;
+                AmbientOverflow::RangeIncreased(range)
+            }
+            None => {
+                let leds = if self.variant.has_green_led() { 3 } else { 2 };
+                let mut reduced = false;
+                for led in 1..=leds {
+                    let amplitude = self.get_led_pulse_amplitude(led)?;
+                    if amplitude > 1 {
+                        self.set_led_pulse_amplitude(led, amplitude / 2)?;
+                        reduced = true;
+                    }
+                }
+                if reduced { AmbientOverflow::CurrentReduced } else { AmbientOverflow::Exhausted }
+            }
+        };
+
+        self.clear_fifo()?;
+        Ok(action)
     }
 
     pub fn get_available_sample_count(&mut self) -> Result<u8, Error<E>> {
-        let mut wr_ptr = [0u8];
-        let mut rd_ptr = [0u8];
+        Ok(self.read_fifo_state()?.available)
+    }
 
-        self.i2c.write_read(self.address, &[FIFO_WR_PTR], &mut wr_ptr)?;
-        self.i2c.write_read(self.address, &[FIFO_RD_PTR], &mut rd_ptr)?;
+    fn read_fifo_state(&mut self) -> Result<FifoState, Error<E>> {
+        // FIFO_WR_PTR, FIFO_OVF_CNT and FIFO_RD_PTR are adjacent
+        let mut pointers = [0u8; 3];
+        self.i2c.write_read(self.address, &[FIFO_WR_PTR], &mut pointers).map_err(Error::read(FIFO_WR_PTR))?;
 
         // Handle 5-bit wraparound correctly
-        let wr = wr_ptr[0] & 0x1F;
-        let rd = rd_ptr[0] & 0x1F;
+        let wr = pointers[0] & 0x1F;
+        let overflow = pointers[1] & 0x1F;
+        let rd = pointers[2] & 0x1F;
 
-        let count = if wr >= rd {
+        let available = if wr > rd {
             wr - rd
-        } else {
+        } else if wr < rd {
             Self::FIFO_DEPTH - rd + wr
+        } else if overflow > 0 {
+            // Equal pointers mean empty, or full once samples started to be dropped
+            Self::FIFO_DEPTH
+        } else {
+            0
         };
 
-        Ok(count)
+        Ok(FifoState { available, overflow, read_pointer: rd })
     }
 
-    pub fn read_fifo_sample(&mut self) -> Result<Option<FifoSample>, Error<E>> {
-        let available = self.get_available_sample_count()?;
+    // Move FIFO_RD_PTR back by `samples` so they are returned again by the next read. Only
+    // samples the chip has not overwritten since can be recovered, i.e. at most 32 minus the
+    // samples currently waiting.
+    pub fn rewind_fifo(&mut self, samples: u8) -> Result<(), Error<E>> {
+        let state = self.read_fifo_state()?;
+        if samples > Self::FIFO_DEPTH - state.available {
+            return Err(Error::ConfigError);
+        }
 
-        if available == 0 {
-            return Ok(None);
+        let read_pointer = (state.read_pointer + Self::FIFO_DEPTH - samples) % Self::FIFO_DEPTH;
+        self.i2c.write(self.address, &[FIFO_RD_PTR, read_pointer]).map_err(Error::write(FIFO_RD_PTR))?;
+        // The rewound samples keep their sequence numbers when read again. A gap that has not
+        // been reached yet moves back by the same amount; one already passed is not undone.
+        self.next_index = self.next_index.wrapping_sub(samples as u32);
+        if let Some((before, _)) = &mut self.pending_gap {
+            *before = before.saturating_add(samples);
         }
+        Ok(())
+    }
+
+    pub fn read_fifo_sample(&mut self) -> Result<Option<FifoSample>, Error<E>> {
+        let mut sample = [FifoSample::default()];
+        let count = self.read_fifo_batch(&mut sample)?;
+        Ok((count > 0).then_some(sample[0]))
+    }
 
-        // Read 6 bytes for SpO2 mode (3 bytes IR + 3 bytes Red)
-        let mut buffer = [0u8; 6];
-        self.i2c.write_read(self.address, &[FIFO_DATA], &mut buffer)?;
+    pub fn read_fifo_batch(&mut self, samples: &mut [FifoSample]) -> Result<usize, Error<E>> {
+        Ok(self.read_samples(samples, Self::decode_sample)?.0)
+    }
 
-        // Parse the data with proper 18-bit masking
-        let ir = (((buffer[0] as u32) << 16) | 
-            ((buffer[1] as u32) << 8) | 
-            (buffer[2] as u32)) & 0x03FFFF;   // Mask to 18-bits
+    // Same as `read_fifo_batch()`, also returning how many samples the chip dropped since the
+    // previous read because the FIFO was full (FIFO_OVF_CNT, saturates at 31). A non-zero count
+    // means there is a gap before the first returned sample with FIFO rollover enabled, or after
+    // the samples that were buffered at the time with rollover disabled.
+    pub fn read_fifo_batch_with_overflow(&mut self, samples: &mut [FifoSample]) -> Result<(usize, u8), Error<E>> {
+        self.read_samples(samples, Self::decode_sample)
+    }
 
-        let red = (((buffer[3] as u32) << 16) |
-            ((buffer[4] as u32) << 8) |
-            (buffer[5] as u32)) & 0x03FFFF;  // Mask to 18-bits
+    // Like `read_fifo_sample()`, keeping every slot of a MultiLed configuration
+    pub fn read_multi_led_sample(&mut self) -> Result<Option<MultiLedSample>, Error<E>> {
+        let mut sample = [MultiLedSample::default()];
+        let count = self.read_multi_led_batch(&mut sample)?;
+        Ok((count > 0).then_some(sample[0]))
+    }
 
-        Ok(Some(FifoSample { red, ir }))
+    pub fn read_multi_led_batch(&mut self, samples: &mut [MultiLedSample]) -> Result<usize, Error<E>> {
+        Ok(self.read_samples(samples, Self::decode_multi_led)?.0)
     }
 
-    pub fn read_fifo_batch(&mut self, samples: &mut [FifoSample]) -> Result<usize, Error<E>> {
-        let available = self.get_available_sample_count()? as usize;
-        let to_read = available.min(samples.len());
+    // Sequence number the next sample read from the FIFO will carry, unless samples are dropped
+    // before then. Every sample the chip produces gets the next number, so a jump between two
+    // consecutive samples is the number of samples lost to a FIFO overflow: before the oldest
+    // buffered sample with rollover enabled, after the newest one with rollover disabled.
+    // FIFO_OVF_CNT saturates at 31, so longer outages are undercounted; `clear_fifo()` discards
+    // samples without numbering them.
+    pub fn next_sample_index(&self) -> u32 {
+        self.next_index
+    }
+
+    // Restart numbering, e.g. together with `clear_fifo()` at the start of a measurement
+    pub fn set_next_sample_index(&mut self, index: u32) {
+        self.next_index = index;
+    }
+
+    // Returns the number of samples read and the overflow count seen before reading. The chip
+    // clears FIFO_OVF_CNT once a sample is popped, so it has to be read first.
+    fn read_samples<T>(&mut self, samples: &mut [T], decode: fn(&Self, &[u8], u32) -> T) -> Result<(usize, u8), Error<E>> {
+        let state = self.read_fifo_state()?;
+        let to_read = (state.available as usize).min(samples.len());
 
         if to_read == 0 {
-            return Ok(0);
+            return Ok((0, state.overflow));
+        }
+
+        let sample_size = self.sample_size();
+        if sample_size == 0 {
+            return Err(Error::ConfigError);
         }
 
         // Use a fixed-size buffer instead of vec!
-        // MAX30102 FIFO holds max 32 samples, so 32 * 6 = 192 bytes max
-        const MAX_BUFFER_SIZE: usize = 192;
+        // MAX30102 FIFO holds max 32 samples of up to 4 LEDs, so 32 * 12 = 384 bytes max
+        const MAX_BUFFER_SIZE: usize = 384;
         let mut buffer = [0u8; MAX_BUFFER_SIZE];
-        
-        let bytes_to_read = to_read * 6;  // 6 bytes per sample in SpO2 mode
-        
+
+        let bytes_to_read = to_read * sample_size;
         if bytes_to_read > MAX_BUFFER_SIZE {
             return Err(Error::ConfigError);
         }
 
-        // Read the data
-        self.i2c.write_read(self.address, &[FIFO_DATA], &mut buffer[..bytes_to_read])?;
+        // The chip advances FIFO_RD_PTR for every sample clocked out, also when the transfer
+        // then fails. Restore the pointer so the samples are read again instead of being lost;
+        // the original bus error is what gets reported.
+        if let Err(e) = self.i2c.write_read(self.address, &[FIFO_DATA], &mut buffer[..bytes_to_read]) {
+            let _ = self.i2c.write(self.address, &[FIFO_RD_PTR, state.read_pointer]);
+            return Err(Error::Register { reg: FIFO_DATA, access: Access::Read, source: e });
+        }
 
-        // Parse the samples
-        for (i, sample) in samples[..to_read].iter_mut().enumerate() {
-            let offset = i * 6;
+        // With rollover the chip overwrote the oldest samples, so the gap comes before everything
+        // still in the FIFO. Without it the newest samples were discarded: the gap follows the
+        // samples buffered now and is only numbered once they have all been read.
+        let mut index = self.next_index;
+        if state.overflow > 0 {
+            if self.fifo_rollover {
+                index = index.wrapping_add(state.overflow as u32);
+            } else {
+                // A second overflow before the first gap was reached is rare (the FIFO has to fill
+                // up again without being drained); both are then counted at the later position
+                let dropped = self.pending_gap.map_or(0, |(_, dropped)| dropped);
+                self.pending_gap = Some((state.available, dropped.saturating_add(state.overflow)));
+            }
+        }
 
-            sample.ir = (((buffer[offset] as u32) << 16) | 
-                ((buffer[offset + 1] as u32) << 8) | 
-                (buffer[offset + 2] as u32)) & 0x03FFFF;
+        for (sample, data) in samples[..to_read].iter_mut().zip(buffer.chunks_exact(sample_size)) {
+            *sample = decode(self, data, index);
+            index = index.wrapping_add(1);
 
-            sample.red = (((buffer[offset + 3] as u32) << 16) | 
-                ((buffer[offset + 4] as u32) << 8) | 
-                (buffer[offset + 5] as u32)) & 0x03FFFF;
+            if let Some((before, dropped)) = &mut self.pending_gap {
+                *before = before.saturating_sub(1);
+                if *before == 0 {
+                    index = index.wrapping_add(*dropped as u32);
+                    self.pending_gap = None;
+                }
+            }
         }
+        self.next_index = index;
+
+        Ok((to_read, state.overflow))
+    }
+
+    // Drain the FIFO sample by sample:
+    //   for sample in sensor.samples() { let sample = sample?; ... }
+    pub fn samples(&mut self) -> FifoSamples<'_, I2C> {
+        FifoSamples {
+            sensor: self,
+            buffer: [FifoSample::default(); SAMPLES_CHUNK],
+            len: 0,
+            position: 0,
+            dropped: 0,
+            done: false,
+        }
+    }
+
+    // Call `f` for every sample currently in the FIFO, returning how many were handled
+    pub fn for_each_sample<F: FnMut(FifoSample)>(&mut self, mut f: F) -> Result<usize, Error<E>> {
+        let mut count = 0;
+        for sample in self.samples() {
+            f(sample?);
+            count += 1;
+        }
+        Ok(count)
+    }
+
+    // Read up to N samples into a stack-allocated array, returning it with the number of valid entries.
+    // N is bounded by the FIFO depth at compile time so stack usage is always explicit.
+    pub fn read_batch<const N: usize>(&mut self) -> Result<([FifoSample; N], usize), Error<E>> {
+        const { assert!(N <= 32, "MAX30102 FIFO holds at most 32 samples") };
+
+        let mut samples = [FifoSample::default(); N];
+        let count = self.read_fifo_batch(&mut samples)?;
+        Ok((samples, count))
+    }
+
+    // Spot check: wake the sensor, fill `out` with fresh samples and shut the sensor down again,
+    // also when reading failed. The FIFO is drained whenever it is about half full, so the
+    // buffer can be longer than the FIFO. Returns the number of samples captured; if the sensor
+    // stops producing data this is less than `out.len()`, or Timeout when nothing arrived.
+    // FifoOverflow means the samples are not contiguous, e.g. because the bus was too slow.
+    pub fn capture_burst(&mut self, out: &mut [FifoSample], delay: &mut impl DelayNs) -> Result<usize, Error<E>> {
+        let rate = self.get_sampling_rate()?.hz() as u32;
+        let averaging = self.get_sample_averaging()?.samples() as u32;
+        let period_us = 1_000_000 * averaging / rate;
+
+        self.wakeup()?;
+        let result = self.fill_burst(out, delay, period_us);
+        let shutdown = self.shutdown();
+
+        let count = result?;
+        shutdown?;
+        Ok(count)
+    }
+
+    fn fill_burst(&mut self, out: &mut [FifoSample], delay: &mut impl DelayNs, period_us: u32) -> Result<usize, Error<E>> {
+        // Give up when no sample arrived for four sample periods plus some slack
+        let max_idle_us = 4 * period_us + 100_000;
 
-        Ok(to_read)
+        self.clear_fifo()?;
+        let mut count = 0;
+        let mut idle_us = 0;
+        while count < out.len() {
+            let wanted = (out.len() - count).min(Self::FIFO_DEPTH as usize / 2) as u32;
+            let wait_us = wanted * period_us;
+            delay.delay_us(wait_us);
+
+            // Dropped samples show up as a jump in the sample numbering, wherever the FIFO
+            // rollover setting puts them. Drops before the first sample only delay the burst.
+            let read = self.read_fifo_batch(&mut out[count..])?;
+            if read > 0 {
+                let last = count + read - 1;
+                let gap = out[last].index.wrapping_sub(out[0].index).wrapping_sub(last as u32);
+                if gap > 0 {
+                    return Err(Error::FifoOverflow(gap.min(u8::MAX as u32) as u8));
+                }
+            }
+            if read == 0 {
+                idle_us += wait_us;
+                if idle_us > max_idle_us {
+                    return if count > 0 { Ok(count) } else { Err(Error::Timeout) };
+                }
+            } else {
+                idle_us = 0;
+            }
+            count += read;
+        }
+        Ok(count)
     }
 
     pub fn clear_fifo(&mut self) -> Result<(), Error<E>> {
         // Reset FIFO read and write pointers
-        self.i2c.write(self.address, &[FIFO_WR_PTR, 0x00])?;
-        self.i2c.write(self.address, &[FIFO_OVF_CNT, 0x00])?;
-        self.i2c.write(self.address, &[FIFO_RD_PTR, 0x00])?;
+        self.i2c.write(self.address, &[FIFO_WR_PTR, 0x00]).map_err(Error::write(FIFO_WR_PTR))?;
+        self.i2c.write(self.address, &[FIFO_OVF_CNT, 0x00]).map_err(Error::write(FIFO_OVF_CNT))?;
+        self.i2c.write(self.address, &[FIFO_RD_PTR, 0x00]).map_err(Error::write(FIFO_RD_PTR))?;
+        self.pending_gap = None;
         Ok(())
     }
 
@@ -422,23 +1481,28 @@ where
         self.enable_interrupt(InterruptSource::TemperatureReady)?;
 
         // Start temperature measurement by writing 0x01 to TEMP_CONFIG
-        self.i2c.write(self.address, &[TEMP_CONFIG, 0x01])?;
+        self.i2c.write(self.address, &[TEMP_CONFIG, 0x01]).map_err(Error::write(TEMP_CONFIG))?;
         Ok(())
     }
 
     pub fn read_temperature(&mut self) -> Result<Option<f32>, Error<E>> {
         // Check if temperature measurement is ready
-        let (_, status2) = self.read_interrupt_status()?;
-
-        if (status2 & 0x02) == 0 {
+        let ready = if self.errata.temperature_ready_unreliable {
+            let mut config = [0u8];
+            self.i2c.write_read(self.address, &[TEMP_CONFIG], &mut config).map_err(Error::read(TEMP_CONFIG))?;
+            config[0] & 0x01 == 0
+        } else {
+            self.read_interrupt_status()?.temperature_ready
+        };
+        if !ready {
             return Ok(None);
         }
 
         let mut temp_int = [0u8];
         let mut temp_frac = [0u8];
 
-        self.i2c.write_read(self.address, &[TEMP_INTR], &mut temp_int)?;
-        self.i2c.write_read(self.address, &[TEMP_FRAC], &mut temp_frac)?;
+        self.i2c.write_read(self.address, &[TEMP_INTR], &mut temp_int).map_err(Error::read(TEMP_INTR))?;
+        self.i2c.write_read(self.address, &[TEMP_FRAC], &mut temp_frac).map_err(Error::read(TEMP_FRAC))?;
 
         // Temperature calculation 
         let integer = temp_int[0] as i8 as f32;
@@ -447,20 +1511,80 @@ where
         Ok(Some(integer + fraction))
     }
 
+    // Trigger a die temperature conversion and wait for the result. TEMP_EN self-clears when the
+    // conversion is done (29 ms typical), which is polled instead of the interrupt flag so the
+    // other pending interrupts are left untouched.
+    pub fn measure_temperature_blocking(&mut self, delay: &mut impl DelayNs) -> Result<f32, Error<E>> {
+        const CONVERSION_TIME_MS: u32 = 29;
+        const POLL_INTERVAL_MS: u32 = 5;
+        const MAX_POLLS: u32 = 10;
+
+        self.i2c.write(self.address, &[TEMP_CONFIG, 0x01]).map_err(Error::write(TEMP_CONFIG))?;
+        delay.delay_ms(CONVERSION_TIME_MS);
+
+        let mut config = [0u8];
+        for _ in 0..MAX_POLLS {
+            self.i2c.write_read(self.address, &[TEMP_CONFIG], &mut config).map_err(Error::read(TEMP_CONFIG))?;
+            if config[0] & 0x01 == 0 {
+                // TEMP_INTR and TEMP_FRAC are adjacent
+                let mut buffer = [0u8; 2];
+                self.i2c.write_read(self.address, &[TEMP_INTR], &mut buffer).map_err(Error::read(TEMP_INTR))?;
+                return Ok(buffer[0] as i8 as f32 + (buffer[1] & 0x0F) as f32 * 0.0625);
+            }
+            delay.delay_ms(POLL_INTERVAL_MS);
+        }
+
+        Err(Error::Timeout)
+    }
+
     pub fn set_multi_led_slots(&mut self, slot1: LedSlot, slot2: LedSlot, slot3: LedSlot, slot4: LedSlot) -> Result<(), Error<E>> {
+        let slots = [slot1, slot2, slot3, slot4];
+        let green = slots.iter().any(|slot| matches!(slot, LedSlot::Led3Green | LedSlot::PilotLed3));
+        if green && !self.variant.has_green_led() {
+            return Err(Error::ConfigError);
+        }
+
         let config1 = ((slot2 as u8) << 4) | (slot1 as u8);
         let config2 = ((slot4 as u8) << 4) | (slot3 as u8);
 
-        self.i2c.write(self.address, &[MULTI_LED_CONFIG1, config1])?;
-        self.i2c.write(self.address, &[MULTI_LED_CONFIG2, config2])?;
+        self.i2c.write(self.address, &[MULTI_LED_CONFIG1, config1]).map_err(Error::write(MULTI_LED_CONFIG1))?;
+        self.i2c.write(self.address, &[MULTI_LED_CONFIG2, config2]).map_err(Error::write(MULTI_LED_CONFIG2))?;
+        self.slots = slots;
         Ok(())
     }
 
     pub fn set_proximity_threshold(&mut self, threshold: u8) -> Result<(), Error<E>> {
-        self.i2c.write(self.address, &[PROX_INT_THRESH, threshold])?;
+        self.i2c.write(self.address, &[PROX_INT_THRESH, threshold]).map_err(Error::write(PROX_INT_THRESH))?;
         Ok(())
     }
 
+    // Start in pilot-only proximity search. `threshold` is compared with the 8 MSBs of the IR
+    // ADC count; once exceeded the chip raises PROX_INT and runs the current operation mode.
+    pub fn enable_proximity_mode(&mut self, pilot_amplitude: u8, threshold: u8) -> Result<(), Error<E>> {
+        self.set_pilot_led_amplitude(pilot_amplitude)?;
+        self.set_proximity_threshold(threshold)?;
+        self.enable_interrupt(InterruptSource::Proximity)?;
+        self.rearm_proximity()
+    }
+
+    pub fn disable_proximity_mode(&mut self) -> Result<(), Error<E>> {
+        self.disable_interrupt(InterruptSource::Proximity)?;
+        self.rearm_proximity()
+    }
+
+    // Rewriting MODE_CONFIG drops the chip back into proximity search (if enabled)
+    pub fn rearm_proximity(&mut self) -> Result<(), Error<E>> {
+        let mode = self.mode;
+        self.set_operation_mode(mode)
+    }
+
+    // PROX_INT flag of INT_STATUS_1, cleared by the read together with the other flags
+    pub fn is_proximity_triggered(&mut self) -> Result<bool, Error<E>> {
+        let mut status = [0u8];
+        self.i2c.write_read(self.address, &[INT_STATUS_1], &mut status).map_err(Error::read(INT_STATUS_1))?;
+        Ok(status[0] & 0x10 != 0)
+    }
+
     pub fn initialize_sensor(&mut self) -> Result<(), Error<E>> {
         // Verify sensor identity
         self.verify_identity()?;
@@ -493,8 +1617,8 @@ where
         self.set_fifo_almost_full_threshold(15)?;
 
         // Set reasonable LED pulse amplitude (Adjustable based on requirements)
-        self.set_led_pulse_amplitude(1, 0x1F)?;   // Red LED
-        self.set_led_pulse_amplitude(2, 0x1F)?;   // IR LED
+        self.set_led_pulse_amplitude(1, 0x1F)?;   // Red LED, 6.2 mA
+        self.set_led_pulse_amplitude(2, 0x1F)?;   // IR LED, 6.2 mA
 
         // Enable FIFO almost full interrupt
         self.enable_interrupt(InterruptSource::FifoAlmostFull)?;
@@ -505,6 +1629,44 @@ where
         Ok(())
     }
 
+    // Reset the chip and apply `config`. Adjacent registers are written together: FIFO, mode and
+    // SpO2 configuration in one transfer, LED amplitudes in another, then slots and interrupts.
+    pub fn initialize_with_config(&mut self, config: &Max30102Config) -> Result<(), Error<E>> {
+        if config.fifo_almost_full_threshold > 15 {
+            return Err(Error::ConfigError);
+        }
+        let green = config.led_amplitudes[2] != 0
+            || config.multi_led_slots.iter().any(|slot| matches!(slot, LedSlot::Led3Green | LedSlot::PilotLed3));
+        if green && !self.variant.has_green_led() {
+            return Err(Error::ConfigError);
+        }
+        let [_, _, spo2_config] = config.register_values();
+        check_timing(Self::leds_per_sample(config.mode, &config.multi_led_slots), spo2_config)?;
+
+        self.verify_identity()?;
+        self.reset()?;
+        self.clear_fifo()?;
+
+        let [fifo, mode, spo2] = config.register_values();
+        self.i2c.write(self.address, &[FIFO_CONFIG, fifo, mode, spo2]).map_err(Error::write(FIFO_CONFIG))?;
+        self.mode = config.mode;
+        self.fifo_rollover = config.fifo_rollover;
+
+        let [red, ir, green] = config.led_amplitudes;
+        if self.variant.has_green_led() {
+            self.i2c.write(self.address, &[LED1_PA, red, ir, green]).map_err(Error::write(LED1_PA))?;
+        } else {
+            self.i2c.write(self.address, &[LED1_PA, red, ir]).map_err(Error::write(LED1_PA))?;
+        }
+
+        let [slot1, slot2, slot3, slot4] = config.multi_led_slots;
+        self.set_multi_led_slots(slot1, slot2, slot3, slot4)?;
+
+        let [enable1, enable2] = config.interrupts;
+        self.i2c.write(self.address, &[INT_ENABLE_1, enable1, enable2]).map_err(Error::write(INT_ENABLE_1))?;
+        Ok(())
+    }
+
     // Heart rate only mode sensor initialization
     pub fn initialize_heart_rate_mode(&mut self) -> Result<(), Error<E>> {
         self.verify_identity()?;
@@ -518,17 +1680,33 @@ where
         self.set_pulse_width(LedPulseWidth::Width411us)?;
         self.set_sample_averaging(SampleAveraging::Average4)?;
 
-        // Only IR LED is used in HeartRate mode
-        self.set_led_pulse_amplitude(2, 0x1F)?;
+        // HeartRate mode only drives LED1 (red)
+        self.set_led_pulse_amplitude(1, 0x1F)?;   // 6.2 mA
 
         self.enable_interrupt(InterruptSource::FifoAlmostFull)?;
         Ok(())
     }
 
+    // MultiLed mode sensor initialization with the same timing as `initialize_sensor()`. Slots
+    // are read out in order up to the first `LedSlot::None`; `amplitudes` are the red, IR and
+    // green LED currents (green must be 0 on the MAX30102). Samples are then read with
+    // `read_multi_led_batch()`, or `read_fifo_batch()` for the red / IR / green view.
+    pub fn initialize_multi_led_mode(&mut self, slots: [LedSlot; 4], amplitudes: [u8; 3]) -> Result<(), Error<E>> {
+        if slots[0] == LedSlot::None {
+            return Err(Error::ConfigError);
+        }
+
+        let config = Max30102Config {
+            led_amplitudes: amplitudes,
+            ..Max30102Config::default().mode(OperationMode::MultiLed).multi_led_slots(slots)
+        };
+        self.initialize_with_config(&config)
+    }
+
     // Get the resolution in bits based on current pulse width scaling
     pub fn get_adc_resolution(&mut self) -> Result<u8, Error<E>> {
         let mut config = [0u8];
-        self.i2c.write_read(self.address, &[SPO2_CONFIG], &mut config)?;
+        self.i2c.write_read(self.address, &[SPO2_CONFIG], &mut config).map_err(Error::read(SPO2_CONFIG))?;
 
         let pulse_width = config[0] & 0x03;
         let resolution = match pulse_width {
@@ -542,24 +1720,202 @@ where
         Ok(resolution)
     }
 
+    pub fn get_adc_range(&mut self) -> Result<AdcRange, Error<E>> {
+        Ok(match (self.read_reg(SPO2_CONFIG)? >> 5) & 0x03 {
+            0x00 => AdcRange::Range2048na,
+            0x01 => AdcRange::Range4096na,
+            0x02 => AdcRange::Range8192na,
+            _ => AdcRange::Range16384na,
+        })
+    }
+
+    pub fn get_sampling_rate(&mut self) -> Result<SamplingRate, Error<E>> {
+        Ok(match (self.read_reg(SPO2_CONFIG)? >> 2) & 0x07 {
+            0x00 => SamplingRate::Rate50,
+            0x01 => SamplingRate::Rate100,
+            0x02 => SamplingRate::Rate200,
+            0x03 => SamplingRate::Rate400,
+            0x04 => SamplingRate::Rate800,
+            0x05 => SamplingRate::Rate1000,
+            0x06 => SamplingRate::Rate1600,
+            _ => SamplingRate::Rate3200,
+        })
+    }
+
+    pub fn get_pulse_width(&mut self) -> Result<LedPulseWidth, Error<E>> {
+        Ok(match self.read_reg(SPO2_CONFIG)? & 0x03 {
+            0x00 => LedPulseWidth::Width69us,
+            0x01 => LedPulseWidth::Width118us,
+            0x02 => LedPulseWidth::Width215us,
+            _ => LedPulseWidth::Width411us,
+        })
+    }
+
+    pub fn get_sample_averaging(&mut self) -> Result<SampleAveraging, Error<E>> {
+        Ok(match self.read_reg(FIFO_CONFIG)? >> 5 {
+            0x00 => SampleAveraging::NoAveraging,
+            0x01 => SampleAveraging::Average2,
+            0x02 => SampleAveraging::Average4,
+            0x03 => SampleAveraging::Average8,
+            0x04 => SampleAveraging::Average16,
+            _ => SampleAveraging::Average32,   // Codes 5-7 all average 32 samples
+        })
+    }
+
+    // `led` is 1 (red), 2 (IR) or 3 (green, MAX30101/MAX30105)
+    pub fn get_led_pulse_amplitude(&mut self, led: u8) -> Result<u8, Error<E>> {
+        let register = match led {
+            1 => LED1_PA,
+            2 => LED2_PA,
+            3 if self.variant.has_green_led() => LED3_PA,
+            _ => return Err(Error::ConfigError),
+        };
+        self.read_reg(register)
+    }
+
+    fn read_reg(&mut self, register: u8) -> Result<u8, Error<E>> {
+        let mut buffer = [0u8];
+        self.i2c.write_read(self.address, &[register], &mut buffer).map_err(Error::read(register))?;
+        Ok(buffer[0])
+    }
+
+    // Snapshot the register map into `buffer`: registers 0x00-0x30 indexed by address, then
+    // REV_ID at 0x31 and PART_ID at 0x32. Registers with read side effects are not read and
+    // appear as 0x00: INT_STATUS_1/2 (clear the interrupts) and FIFO_DATA (pops the FIFO).
+    // Returns the number of bytes written, up to REGISTER_DUMP_SIZE.
+    pub fn dump_registers(&mut self, buffer: &mut [u8]) -> Result<usize, Error<E>> {
+        const MAP_SIZE: usize = 0x31;
+        let length = buffer.len().min(Self::REGISTER_DUMP_SIZE);
+        // INT_STATUS_2 directly follows INT_STATUS_1
+        let skipped = [INT_STATUS_1, INT_STATUS_1 + 1, FIFO_DATA];
+
+        let map_length = length.min(MAP_SIZE);
+        let mut start = 0usize;
+        while start < map_length {
+            if skipped.contains(&(start as u8)) {
+                buffer[start] = 0x00;
+                start += 1;
+                continue;
+            }
+
+            // Burst-read up to the next skipped register
+            let end = skipped
+                .iter()
+                .map(|&register| register as usize)
+                .filter(|&register| register > start)
+                .min()
+                .unwrap_or(map_length)
+                .min(map_length);
+            self.i2c.write_read(self.address, &[start as u8], &mut buffer[start..end]).map_err(Error::read(start as u8))?;
+            start = end;
+        }
+
+        if length > MAP_SIZE {
+            // REV_ID and PART_ID are adjacent
+            let mut identity = [0u8; 2];
+            self.i2c.write_read(self.address, &[REV_ID], &mut identity).map_err(Error::read(REV_ID))?;
+            let count = length - MAP_SIZE;
+            buffer[MAP_SIZE..length].copy_from_slice(&identity[..count]);
+        }
+
+        Ok(length)
+    }
+
+    // Check the configuration for the usual reasons a sensor returns no or flat data. When the
+    // sensor should be sampling, waits three sample periods to see whether the FIFO advances.
+    pub fn diagnose(&mut self, delay: &mut impl DelayNs) -> Result<Diagnosis, Error<E>> {
+        let mut registers = [0u8; REGISTER_DUMP_SIZE];
+        self.dump_registers(&mut registers)?;
+
+        let mode_config = registers[MODE_CONFIG as usize];
+        let fifo_config = registers[FIFO_CONFIG as usize];
+        let spo2_config = registers[SPO2_CONFIG as usize];
+        let slot_codes = [
+            registers[MULTI_LED_CONFIG1 as usize] & 0x07,
+            (registers[MULTI_LED_CONFIG1 as usize] >> 4) & 0x07,
+            registers[MULTI_LED_CONFIG2 as usize] & 0x07,
+            (registers[MULTI_LED_CONFIG2 as usize] >> 4) & 0x07,
+        ];
+
+        let mut diagnosis = Diagnosis {
+            part_id_mismatch: registers[0x32] != Self::EXPECTED_PART_ID,
+            shutdown: mode_config & 0x80 != 0,
+            fifo_overflowed: registers[FIFO_OVF_CNT as usize] & 0x1F != 0,
+            ..Diagnosis::default()
+        };
+
+        // LED (1-3) behind each word of a sample, up to the first disabled slot. Pilot slots
+        // use PILOT_PA and are not checked.
+        let mut leds = [0u8; 4];
+        let active = match mode_config & 0x07 {
+            0x02 => {
+                leds[0] = 1;
+                1
+            }
+            0x03 => {
+                leds[..2].copy_from_slice(&[1, 2]);
+                2
+            }
+            0x07 => {
+                let active = slot_codes.iter().take_while(|&&code| code != 0).count();
+                for (led, &code) in leds.iter_mut().zip(&slot_codes[..active]) {
+                    *led = if (1..=3).contains(&code) { code } else { 0 };
+                }
+                diagnosis.no_slots = active == 0;
+                active
+            }
+            _ => {
+                diagnosis.invalid_mode = true;
+                0
+            }
+        };
+
+        for &led in leds[..active].iter().filter(|&&led| led != 0) {
+            if registers[(LED1_PA + led - 1) as usize] == 0 {
+                diagnosis.dark_leds[led as usize - 1] = true;
+            }
+        }
+        diagnosis.timing_violation = active > 0 && check_timing::<E>(active, spo2_config).is_err();
+
+        if !diagnosis.part_id_mismatch && !diagnosis.shutdown && active > 0 {
+            const RATES_HZ: [u32; 8] = [50, 100, 200, 400, 800, 1000, 1600, 3200];
+            let averaging = 1u32 << (fifo_config >> 5).min(5);
+            let period_us = 1_000_000 * averaging / RATES_HZ[((spo2_config >> 2) & 0x07) as usize];
+
+            // FIFO_WR_PTR and FIFO_OVF_CNT are adjacent. With rollover disabled a full FIFO
+            // stops the write pointer but keeps counting dropped samples, which is not a stall.
+            let before = [registers[FIFO_WR_PTR as usize], registers[FIFO_OVF_CNT as usize]];
+            delay.delay_us(3 * period_us + 1_000);
+            let mut after = [0u8; 2];
+            self.i2c.write_read(self.address, &[FIFO_WR_PTR], &mut after).map_err(Error::read(FIFO_WR_PTR))?;
+            diagnosis.fifo_stalled = before == after && after[1] & 0x1F != 0x1F;
+        }
+
+        Ok(diagnosis)
+    }
+
     // Check if sensor is in shutdown mode
     pub fn is_shutdown(&mut self) -> Result<bool, Error<E>> {
         let mut config = [0u8];
-        self.i2c.write_read(self.address, &[MODE_CONFIG], &mut config)?;
+        self.i2c.write_read(self.address, &[MODE_CONFIG], &mut config).map_err(Error::read(MODE_CONFIG))?;
         Ok((config[0] & 0x80) != 0)
     }
 
     // Get current operation mode
     pub fn get_operation_mode(&mut self) -> Result<OperationMode, Error<E>> {
         let mut config = [0u8];
-        self.i2c.write_read(self.address, &[MODE_CONFIG], &mut config)?;
+        self.i2c.write_read(self.address, &[MODE_CONFIG], &mut config).map_err(Error::read(MODE_CONFIG))?;
 
-        match config[0] & 0x07 {
-            0x02 => Ok(OperationMode::HeartRate),
-            0x03 => Ok(OperationMode::SpO2),
-            0x07 => Ok(OperationMode::MultiLed),
-            _ => Err(Error::InvalidData),
-        }
+        let mode = match config[0] & 0x07 {
+            0x02 => OperationMode::HeartRate,
+            0x03 => OperationMode::SpO2,
+            0x07 => OperationMode::MultiLed,
+            _ => return Err(Error::InvalidData),
+        };
+
+        // Keep the FIFO layout in sync with a mode set behind the driver's back
+        self.mode = mode;
+        Ok(mode)
     }
 
     pub fn validate_configuration(&mut self) -> Result<(), Error<E>> {
@@ -575,10 +1931,10 @@ where
 
         // Check FIFO is not overflowing
         let mut overflow_count = [0u8];
-        self.i2c.write_read(self.address, &[FIFO_OVF_CNT], &mut overflow_count)?;
+        self.i2c.write_read(self.address, &[FIFO_OVF_CNT], &mut overflow_count).map_err(Error::read(FIFO_OVF_CNT))?;
 
         if overflow_count[0] > 0 {
-            return Err(Error::SensorSpecific("FIFO overflow detected"));
+            return Err(Error::FifoOverflow(overflow_count[0] & 0x1F));
         }
 
         Ok(())
@@ -609,4 +1965,62 @@ where
 
         Ok(())
     }
+
+    // RESET in MODE_CONFIG clears itself once the registers are back at their defaults
+    fn wait_reset_complete(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<E>> {
+        const POLL_INTERVAL_MS: u32 = 1;
+        const MAX_POLLS: u32 = 10;
+
+        for _ in 0..MAX_POLLS {
+            let mut config = [0u8];
+            self.i2c.write_read(self.address, &[MODE_CONFIG], &mut config).map_err(Error::read(MODE_CONFIG))?;
+            if config[0] & 0x40 == 0 {
+                return Ok(());
+            }
+            delay.delay_ms(POLL_INTERVAL_MS);
+        }
+        Err(Error::Timeout)
+    }
+}
+
+// Every LED pulse has to fit into the sample period, so the datasheet limits the sampling rate
+// per pulse width: 3200 / 1600 / 1000 / 800 sps for 69 / 118 / 215 / 411 us with one LED, one
+// rate step lower with two. Multi-LED configurations with more slots are held to the two-LED
+// limits. `spo2_config` is a SPO2_CONFIG register value.
+#[cfg(feature = "max30102")]
+fn check_timing<E>(leds: usize, spo2_config: u8) -> Result<(), Error<E>> {
+    let rate = (spo2_config >> 2) & 0x07;
+    let width = spo2_config & 0x03;
+    let max_rate = 7 - width - (leds >= 2) as u8;
+
+    if rate > max_rate {
+        return Err(Error::SensorSpecific("Sampling rate too high for the LED pulse width"));
+    }
+    Ok(())
+}
+
+#[cfg(feature = "max30102")]
+impl<I2C, E> Recover<E> for Max30102<I2C>
+where
+    I2C: I2c<Error = E>
+{
+    fn recover(&mut self, action: RecoveryAction, delay: &mut impl DelayNs) -> Result<(), Error<E>> {
+        match action {
+            RecoveryAction::SignalPathReset => self.clear_fifo(),
+            RecoveryAction::ForceReset => {
+                self.force_reset()?;
+                self.wait_reset_complete(delay)
+            }
+            RecoveryAction::Reinitialize => {
+                // Keep heart-rate-only setups in heart-rate mode after the reset
+                let mode = self.get_operation_mode().unwrap_or(OperationMode::SpO2);
+                self.force_reset()?;
+                self.wait_reset_complete(delay)?;
+                match mode {
+                    OperationMode::HeartRate => self.initialize_heart_rate_mode(),
+                    _ => self.initialize_sensor(),
+                }
+            }
+        }
+    }
 }
@@ -1,11 +1,40 @@
 #[cfg(feature = "mpu6050")]
 use embedded_hal::i2c::I2c;
 
+#[cfg(feature = "mpu6050")]
+use embedded_hal::delay::DelayNs;
+
 #[cfg(feature = "mpu6050")]
 use crate::error::Error;
 
+#[cfg(feature = "mpu6050")]
+use crate::device::{Device, DeviceInfo};
+
+#[cfg(feature = "mpu6050")]
+use crate::validation::Limits;
+
+#[cfg(feature = "mpu6050")]
+use crate::frame::{ImuFrameView, IMU_FRAME_SIZE};
+
+#[cfg(feature = "mpu6050")]
+use crate::watchdog::{Recover, RecoveryAction};
+
+#[cfg(feature = "mpu6050")]
+use crate::imu::Imu;
+
+#[cfg(feature = "mpu6050")]
+use crate::mpu_common::{self, ACCEL_OFFSET_MAX, ACCEL_OFFSET_MIN, round_to_i16};
+
 #[cfg(feature = "mpu6050")]
 mod registers {
+    pub const XA_OFFS_H: u8 = 0x06;     // YA and ZA offsets follow
+    pub const YA_OFFS_H: u8 = 0x08;
+    pub const ZA_OFFS_H: u8 = 0x0A;
+    pub const XA_OFFSET_H_6500: u8 = 0x77;   // MPU6500/MPU6886 accel offsets, 3 registers apart
+    pub const YA_OFFSET_H_6500: u8 = 0x7A;
+    pub const ZA_OFFSET_H_6500: u8 = 0x7D;
+    pub const SELF_TEST_X: u8 = 0x0D;   // SELF_TEST_Y, SELF_TEST_Z and SELF_TEST_A follow
+    pub const XG_OFFS_USRH: u8 = 0x13;  // YG and ZG offsets follow
     pub const WHO_AM_I: u8 = 0x75;
     //pub const WHO_AM_I_VALUE: u8 = 0x68; // Changed from 0x74 for MPU6050
     pub const PWR_MGMT_1: u8 = 0x6B;
@@ -16,11 +45,66 @@ mod registers {
     pub const GYRO_XOUT_H: u8 = 0x43;
     pub const SMPRT_DIV: u8 = 0x19;
     pub const CONFIG: u8 = 0x1A;
+    pub const SIGNAL_PATH_RESET: u8 = 0x68;
+    pub const FF_THR: u8 = 0x1D;
+    pub const FF_DUR: u8 = 0x1E;
+    pub const MOT_THR: u8 = 0x1F;
+    pub const MOT_DUR: u8 = 0x20;
+    pub const MOT_DETECT_STATUS: u8 = 0x61;
+    pub const MOT_DETECT_CTRL: u8 = 0x69;
+    pub const FIFO_EN: u8 = 0x23;
+    pub const I2C_MST_CTRL: u8 = 0x24;
+    pub const I2C_SLV0_ADDR: u8 = 0x25;   // REG and CTRL follow, slaves 1-3 every 3 registers
+    pub const I2C_SLV4_ADDR: u8 = 0x31;
+    pub const I2C_SLV4_REG: u8 = 0x32;
+    pub const I2C_SLV4_DO: u8 = 0x33;
+    pub const I2C_SLV4_CTRL: u8 = 0x34;
+    pub const I2C_SLV4_DI: u8 = 0x35;
+    pub const I2C_MST_STATUS: u8 = 0x36;
+    pub const EXT_SENS_DATA_00: u8 = 0x49;
+    pub const I2C_SLV0_DO: u8 = 0x63;
+    pub const INT_PIN_CFG: u8 = 0x37;
+    pub const INT_ENABLE: u8 = 0x38;
+    pub const INT_STATUS: u8 = 0x3A;
+    pub const USER_CTRL: u8 = 0x6A;
+    pub const PWR_MGMT_2: u8 = 0x6C;
+    pub const FIFO_COUNT_H: u8 = 0x72;
+    pub const FIFO_R_W: u8 = 0x74;
+    #[cfg(feature = "dmp")]
+    pub const BANK_SEL: u8 = 0x6D;
+    #[cfg(feature = "dmp")]
+    pub const MEM_START_ADDR: u8 = 0x6E;
+    #[cfg(feature = "dmp")]
+    pub const MEM_R_W: u8 = 0x6F;
+    #[cfg(feature = "dmp")]
+    pub const PRGM_START_H: u8 = 0x70;
 }
 
 #[cfg(feature = "mpu6050")]
 use registers::*;
 
+// DMP memory locations patched by `configure_dmp()`. They are offsets into the InvenSense motion
+// driver 6.12 image (3062 bytes, start address 0x0400) and mean nothing for other images.
+#[cfg(all(feature = "mpu6050", feature = "dmp"))]
+mod dmp_memory {
+    pub const D_0_22: u16 = 22 + 512;           // FIFO rate divider
+    pub const D_0_104: u16 = 104;               // Gyro integration scale factor
+    pub const CFG_6: u16 = 2753;                // FIFO rate program
+    pub const CFG_8: u16 = 2718;                // 6-axis quaternion output
+    pub const CFG_15: u16 = 2727;               // Raw accel and gyro output
+    pub const CFG_20: u16 = 2224;               // Tap detection
+    pub const CFG_27: u16 = 2742;               // Gesture output
+    pub const CFG_LP_QUAT: u16 = 2712;          // 3-axis quaternion output
+    pub const CFG_GYRO_RAW_DATA: u16 = 2722;    // Raw or calibrated gyro source
+    pub const CFG_MOTION_BIAS: u16 = 1208;      // Gyro bias calibration
+    pub const CFG_ANDROID_ORIENT_INT: u16 = 1853;
+
+    // Internal DMP rate the image's constants are built for
+    pub const SAMPLE_RATE: u16 = 200;
+    // Gyro integration factor for 2000 dps at SAMPLE_RATE
+    pub const GYRO_SF: u32 = 46850825;
+}
+
 #[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
 #[cfg(feature = "mpu6050")]
 pub struct Mpu6050<I2C> {
@@ -28,9 +112,204 @@ pub struct Mpu6050<I2C> {
     address: u8,
     accel_scale: f32,
     gyro_scale: f32,
+    accel_range: AccelRange,
+    gyro_range: GyroRange,
+    fifo_config: FifoConfig,
+    variant: Option<ChipVariant>,
+    accel_scale_correction: [f32; 3],
+    gyro_scale_correction: [f32; 3],
+    fsync_location: FsyncLocation,
+    power_snapshot: Option<[u8; 3]>,   // CONFIG, PWR_MGMT_1, PWR_MGMT_2 saved by enter_minimum_power()
+    temperature_enabled: bool,         // TEMP_DIS clear, tracked through the driver's PWR_MGMT_1 writes
+    #[cfg(feature = "dmp")]
+    dmp_config: Option<DmpConfig>,   // Packet layout written by configure_dmp()
+    #[cfg(feature = "dmp")]
+    dmp_packet_size: usize,
+}
+
+// I2C address selected by the AD0 pin
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub enum Address {
+    Ad0Low = 0x68,
+    Ad0High = 0x69,
+}
+
+#[cfg(feature = "mpu6050")]
+impl From<Address> for u8 {
+    fn from(address: Address) -> u8 {
+        address as u8
+    }
+}
+
+// CLKSEL values in PWR_MGMT_1
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub enum ClockSource {
+    Internal = 0,          // 8 MHz internal oscillator
+    PllGyroX = 1,          // Recommended: better stability than the internal oscillator
+    PllGyroY = 2,
+    PllGyroZ = 3,
+    PllExternal32kHz = 4,
+    PllExternal19MHz = 5,
+    Stop = 7,              // Stops the clock and keeps the timing generator in reset
+}
+
+// Power management state read back from PWR_MGMT_1 and PWR_MGMT_2
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub struct PowerConfig {
+    pub clock_source: ClockSource,
+    pub sleep: bool,
+    pub cycle: bool,
+    pub temperature_disabled: bool,
+    pub wake_rate: LowPowerWakeRate,
+    pub standby: AxisStandby,
+}
+
+// Complete sensor configuration, applied in a safe order by `Mpu6050::new_with_config()` or
+// `apply_config()`. Start from `Mpu6050Config::default()` (the chip's power-on settings with
+// the X gyro PLL clock) and override what is needed:
+//   Mpu6050Config::default().accel_range(AccelRange::Range8G).sample_rate_divider(9)
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub struct Mpu6050Config {
+    pub accel_range: AccelRange,
+    pub gyro_range: GyroRange,
+    pub dlpf: DlpfConfig,
+    pub sample_rate_divider: u8,
+    pub clock_source: ClockSource,
+    pub fsync: FsyncLocation,
+    pub interrupt_pin: InterruptPinConfig,
+    pub interrupts: u8,               // INT_ENABLE mask built from InterruptSource values
+    pub temperature_enabled: bool,
+    pub standby: AxisStandby,
+}
+
+#[cfg(feature = "mpu6050")]
+impl Default for Mpu6050Config {
+    fn default() -> Self {
+        Mpu6050Config {
+            accel_range: AccelRange::Range2G,
+            gyro_range: GyroRange::Range250Dps,
+            dlpf: DlpfConfig::Bandwidth260Hz,
+            sample_rate_divider: 0,
+            clock_source: ClockSource::PllGyroX,
+            fsync: FsyncLocation::Disabled,
+            interrupt_pin: InterruptPinConfig::default(),
+            interrupts: 0,
+            temperature_enabled: true,
+            standby: AxisStandby::default(),
+        }
+    }
+}
+
+#[cfg(feature = "mpu6050")]
+impl Mpu6050Config {
+    pub fn accel_range(mut self, range: AccelRange) -> Self {
+        self.accel_range = range;
+        self
+    }
+
+    pub fn gyro_range(mut self, range: GyroRange) -> Self {
+        self.gyro_range = range;
+        self
+    }
+
+    pub fn dlpf(mut self, dlpf: DlpfConfig) -> Self {
+        self.dlpf = dlpf;
+        self
+    }
+
+    pub fn sample_rate_divider(mut self, divider: u8) -> Self {
+        self.sample_rate_divider = divider;
+        self
+    }
+
+    pub fn clock_source(mut self, source: ClockSource) -> Self {
+        self.clock_source = source;
+        self
+    }
+
+    pub fn fsync(mut self, location: FsyncLocation) -> Self {
+        self.fsync = location;
+        self
+    }
+
+    pub fn interrupt_pin(mut self, config: InterruptPinConfig) -> Self {
+        self.interrupt_pin = config;
+        self
+    }
+
+    pub fn enable_interrupt(mut self, interrupt: InterruptSource) -> Self {
+        self.interrupts |= interrupt as u8;
+        self
+    }
+
+    pub fn temperature_enabled(mut self, enabled: bool) -> Self {
+        self.temperature_enabled = enabled;
+        self
+    }
+
+    pub fn standby(mut self, standby: AxisStandby) -> Self {
+        self.standby = standby;
+        self
+    }
+}
+
+// Parts found on boards sold as MPU6050. The MPU6500 and MPU6886 share the basic register map
+// (ranges, data registers, DLPF, sample rate) but differ in temperature scaling, accel offset
+// register location and the self-test and low-power accel blocks.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub enum ChipVariant {
+    Mpu6050,
+    Mpu6500,
+    Mpu6886,
+}
+
+#[cfg(feature = "mpu6050")]
+impl ChipVariant {
+    pub fn from_who_am_i(value: u8) -> Option<Self> {
+        match value {
+            0x68 | 0x69 | 0x98 => Some(ChipVariant::Mpu6050),   // 0x98 is reported by common clones
+            0x70 => Some(ChipVariant::Mpu6500),
+            0x19 => Some(ChipVariant::Mpu6886),
+            _ => None,
+        }
+    }
+
+    pub fn device(&self) -> Device {
+        match self {
+            ChipVariant::Mpu6050 => Device::Mpu6050,
+            ChipVariant::Mpu6500 => Device::Mpu6500,
+            ChipVariant::Mpu6886 => Device::Mpu6886,
+        }
+    }
+
+    // Degrees Celsius from TEMP_OUT
+    pub fn temperature_celsius(&self, raw: i16) -> f32 {
+        match self {
+            ChipVariant::Mpu6050 => (raw as f32) / 340.0 + 36.53,
+            ChipVariant::Mpu6500 => (raw as f32) / 333.87 + 21.0,
+            ChipVariant::Mpu6886 => (raw as f32) / 326.8 + 25.0,
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
 pub enum AccelRange {
     Range2G,
@@ -40,6 +319,8 @@ pub enum AccelRange {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
 pub enum GyroRange {
     Range250Dps,
@@ -49,6 +330,8 @@ pub enum GyroRange {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
 pub enum DlpfConfig {
     Bandwidth260Hz,
@@ -60,177 +343,2195 @@ pub enum DlpfConfig {
     Bandwidth5Hz,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub struct RawSample {
+    pub accel: [i16; 3],
+    pub temp: i16,
+    pub gyro: [i16; 3],
+}
+
 #[cfg(feature = "mpu6050")]
-impl<I2C, E> Mpu6050<I2C>
-where 
-    I2C: I2c<Error = E>
-{
-    pub fn new(i2c: I2C, address: u8) -> Self {
-        Mpu6050 {
-            i2c,
-            address,
-            accel_scale: 0.0,
-            gyro_scale: 0.0,
-        }
+impl RawSample {
+    // State of the FSYNC pin latched into the LSB selected by `location`, None if FSYNC is disabled
+    pub fn fsync_flag(&self, location: FsyncLocation) -> Option<bool> {
+        let word = match location {
+            FsyncLocation::Disabled => return None,
+            FsyncLocation::TempOutL => self.temp,
+            FsyncLocation::GyroXoutL => self.gyro[0],
+            FsyncLocation::GyroYoutL => self.gyro[1],
+            FsyncLocation::GyroZoutL => self.gyro[2],
+            FsyncLocation::AccelXoutL => self.accel[0],
+            FsyncLocation::AccelYoutL => self.accel[1],
+            FsyncLocation::AccelZoutL => self.accel[2],
+        };
+        Some(word & 0x01 != 0)
     }
+}
 
-    pub fn verify_identity(&mut self) -> Result<(), Error<E>> {
-        let mut buffer = [0u8];
-        self.i2c.write_read(self.address, &[WHO_AM_I], &mut buffer)?;
+// EXT_SYNC_SET: output register whose LSB is replaced by the FSYNC pin state latched since
+// the previous sample
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub enum FsyncLocation {
+    #[default]
+    Disabled = 0x00,
+    TempOutL = 0x01,
+    GyroXoutL = 0x02,
+    GyroYoutL = 0x03,
+    GyroZoutL = 0x04,
+    AccelXoutL = 0x05,
+    AccelYoutL = 0x06,
+    AccelZoutL = 0x07,
+}
 
-        match buffer[0] {
-            0x68 | 0x69 | 0x70 | 0x98 => Ok(()),
-            _ => Err(Error::NotDetected)
-        }
-    }
+// All channels of one burst read. The driver has no clock, so `timestamp` is left for the
+// application to fill in (e.g. with `with_timestamp()`) in whatever unit its timer uses.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub struct Sample {
+    pub acceleration: [f32; 3],       // g
+    pub angular_velocity: [f32; 3],   // dps
+    pub temperature: Option<f32>,     // Celsius, None while the temperature sensor is disabled
+    pub timestamp: Option<u32>,
+}
 
-    pub fn configure_power(&mut self) -> Result<(), Error<E>> {
-        // Reset device first, then set clock source to PLL with X axis gyroscope reference
-        self.i2c.write(self.address, &[PWR_MGMT_1, 0x80])?; // Reset
-                                                            // Small delay would be needed here in real implementation
-        let config = 0x01; // Clock source: PLL with X axis gyroscope reference
-        self.i2c.write(self.address, &[PWR_MGMT_1, config])?;
-        Ok(())
+#[cfg(feature = "mpu6050")]
+impl Sample {
+    pub fn with_timestamp(self, timestamp: u32) -> Self {
+        Sample { timestamp: Some(timestamp), ..self }
     }
+}
 
-    pub fn setup_accelerometer(&mut self, range: AccelRange) -> Result<(), Error<E>> {
-        let (config_value, scale) = match range {
-            AccelRange::Range2G => (0x00, 2.0 / 32768.0),
-            AccelRange::Range4G => (0x08, 4.0 / 32768.0),
-            AccelRange::Range8G => (0x10, 8.0 / 32768.0),
-            AccelRange::Range16G => (0x18, 16.0 / 32768.0),
-        };
-        self.i2c.write(self.address, &[ACCEL_CONFIG, config_value])?;
-        self.accel_scale = scale;
-        Ok(())
-    }
+// Channels pushed into the 1024-byte FIFO. Each frame holds the enabled channels in register
+// order: accel XYZ, temperature, then gyro X, Y and Z, two bytes each.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub struct FifoConfig {
+    pub temperature: bool,
+    pub gyro_x: bool,
+    pub gyro_y: bool,
+    pub gyro_z: bool,
+    pub accel: bool,
+}
 
-    pub fn setup_gyroscope(&mut self, range: GyroRange) -> Result<(), Error<E>> {
-        let (config_value, scale) = match range {
-            GyroRange::Range250Dps => (0x00, 250.0 / 32768.0),
-            GyroRange::Range500Dps => (0x08, 500.0 / 32768.0),
-            GyroRange::Range1000Dps => (0x10, 1000.0 / 32768.0),
-            GyroRange::Range2000Dps => (0x18, 2000.0 / 32768.0),
-        };
-        self.i2c.write(self.address, &[GYRO_CONFIG, config_value])?;
-        self.gyro_scale = scale;
-        Ok(())
+#[cfg(feature = "mpu6050")]
+impl FifoConfig {
+    // Accel, temperature and all gyro axes, 14 bytes per frame
+    pub fn all() -> Self {
+        FifoConfig {
+            temperature: true,
+            gyro_x: true,
+            gyro_y: true,
+            gyro_z: true,
+            accel: true,
+        }
     }
 
-    pub fn initialize_sensor(&mut self, accel_range: AccelRange, gyro_range: GyroRange) -> Result<(), Error<E>> {
-        self.verify_identity()?;
-        self.configure_power()?;
-        self.setup_accelerometer(accel_range)?;
-        self.setup_gyroscope(gyro_range)?;
-        Ok(())
+    // FIFO_EN bits: TEMP (7), XG (6), YG (5), ZG (4), ACCEL (3)
+    pub fn to_bits(&self) -> u8 {
+        (self.temperature as u8) << 7
+            | (self.gyro_x as u8) << 6
+            | (self.gyro_y as u8) << 5
+            | (self.gyro_z as u8) << 4
+            | (self.accel as u8) << 3
     }
 
-    pub fn read_accel_raw(&mut self) -> Result<[i16; 3], Error<E>> {
-        let mut buffer = [0u8; 6];
-        self.i2c.write_read(self.address, &[ACCEL_XOUT_H], &mut buffer)?;
-        let x = ((buffer[0] as i16) << 8) | buffer[1] as i16;
-        let y = ((buffer[2] as i16) << 8) | buffer[3] as i16;
-        let z = ((buffer[4] as i16) << 8) | buffer[5] as i16;
-        Ok([x, y, z])
+    pub fn frame_size(&self) -> usize {
+        (self.accel as usize) * 6
+            + (self.temperature as usize) * 2
+            + (self.gyro_x as usize + self.gyro_y as usize + self.gyro_z as usize) * 2
     }
+}
 
-    pub fn read_gyro_raw(&mut self) -> Result<[i16; 3], Error<E>> {
-        let mut buffer = [0u8; 6];
-        self.i2c.write_read(self.address, &[GYRO_XOUT_H], &mut buffer)?;
-        let x = ((buffer[0] as i16) << 8) | buffer[1] as i16;
-        let y = ((buffer[2] as i16) << 8) | buffer[3] as i16;
-        let z = ((buffer[4] as i16) << 8) | buffer[5] as i16;
-        Ok([x, y, z])
-    }
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub enum InterruptSource {
+    FreeFall = 0x80,       // Bit 7 of INT_ENABLE
+    Motion = 0x40,         // Bit 6 of INT_ENABLE
+    ZeroMotion = 0x20,     // Bit 5 of INT_ENABLE
+    FifoOverflow = 0x10,   // Bit 4 of INT_ENABLE
+    I2cMaster = 0x08,      // Bit 3 of INT_ENABLE
+    DataReady = 0x01,      // Bit 0 of INT_ENABLE
+}
 
-    pub fn read_temp_raw(&mut self) -> Result<i16, Error<E>> {
-        let mut buffer = [0u8; 2];
-        self.i2c.write_read(self.address, &[TEMP_OUT_H], &mut buffer)?;
-        let temp = ((buffer[0] as i16) << 8) | buffer[1] as i16;
-        Ok(temp)
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub enum InterruptLevel {
+    #[default]
+    ActiveHigh,
+    ActiveLow,
+}
 
-    pub fn read_acceleration(&mut self) -> Result<[f32; 3], Error<E>> {
-        let raw = self.read_accel_raw()?;
-        let x = raw[0] as f32 * self.accel_scale;
-        let y = raw[1] as f32 * self.accel_scale;
-        let z = raw[2] as f32 * self.accel_scale;
-        Ok([x, y, z])
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub enum InterruptDrive {
+    #[default]
+    PushPull,
+    OpenDrain,             // Needed when several devices share one interrupt line
+}
 
-    pub fn read_angular_velocity(&mut self) -> Result<[f32; 3], Error<E>> {
-        let raw = self.read_gyro_raw()?;
-        let x = raw[0] as f32 * self.gyro_scale;
-        let y = raw[1] as f32 * self.gyro_scale;
-        let z = raw[2] as f32 * self.gyro_scale;
-        Ok([x, y, z])
-    }
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub enum InterruptLatch {
+    #[default]
+    Pulse50us,             // INT pin emits a 50us pulse
+    UntilCleared,          // INT pin held until the interrupt is cleared
+}
 
-    pub fn read_temperature_celsius(&mut self) -> Result<f32, Error<E>> {
-        let raw = self.read_temp_raw()?;
-        // MPU6050 temperature formula: Temperature in degrees C = (TEMP_OUT Register Value as a signed 16-bit value)/340 + 36.53
-        let temperature = (raw as f32) / 340.0 + 36.53;
-        Ok(temperature)
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub struct InterruptPinConfig {
+    pub level: InterruptLevel,
+    pub drive: InterruptDrive,
+    pub latch: InterruptLatch,
+    pub clear_on_any_read: bool,   // Clear on any register read instead of only on INT_STATUS reads
+}
+
+// Axes to put into standby through PWR_MGMT_2, true disables the axis. Each gyro axis left
+// running costs roughly 1.2 mA.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub struct AxisStandby {
+    pub accel_x: bool,
+    pub accel_y: bool,
+    pub accel_z: bool,
+    pub gyro_x: bool,
+    pub gyro_y: bool,
+    pub gyro_z: bool,
+}
+
+#[cfg(feature = "mpu6050")]
+impl AxisStandby {
+    // STBY_XA..STBY_ZG, bits 5:0
+    pub fn to_bits(&self) -> u8 {
+        (self.accel_x as u8) << 5
+            | (self.accel_y as u8) << 4
+            | (self.accel_z as u8) << 3
+            | (self.gyro_x as u8) << 2
+            | (self.gyro_y as u8) << 1
+            | (self.gyro_z as u8)
     }
 
-    pub fn set_sample_rate(&mut self, divider: u8) -> Result<(), Error<E>> {
-        // Sample Rate = Gyroscope Output Rate / (1 + SMPLRT_DIV)
-        // Gyroscope Output Rate = 8kHz when the DLPF is disabled (DLPF_CFG = 0 or 7), and 1kHz when the DLPF is enabled
-        self.i2c.write(self.address, &[SMPRT_DIV, divider])?;
-        Ok(())
+    pub fn from_bits(bits: u8) -> Self {
+        AxisStandby {
+            accel_x: bits & 0x20 != 0,
+            accel_y: bits & 0x10 != 0,
+            accel_z: bits & 0x08 != 0,
+            gyro_x: bits & 0x04 != 0,
+            gyro_y: bits & 0x02 != 0,
+            gyro_z: bits & 0x01 != 0,
+        }
     }
+}
 
-    pub fn set_dlpf_config(&mut self, config: DlpfConfig) -> Result<(), Error<E>> {
-        let config_value = match config {
-            DlpfConfig::Bandwidth260Hz => 0x00,
-            DlpfConfig::Bandwidth184Hz => 0x01,
-            DlpfConfig::Bandwidth94Hz => 0x02,
-            DlpfConfig::Bandwidth44Hz => 0x03,
-            DlpfConfig::Bandwidth21Hz => 0x04,
-            DlpfConfig::Bandwidth10Hz => 0x05,
-            DlpfConfig::Bandwidth5Hz => 0x06,
-        };
-        self.i2c.write(self.address, &[CONFIG, config_value])?;
-        Ok(())
+// Clock of the auxiliary I2C master (I2C_MST_CLK)
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub enum I2cMasterClock {
+    Clock348kHz = 0x00,
+    Clock333kHz = 0x01,
+    Clock320kHz = 0x02,
+    Clock308kHz = 0x03,
+    Clock296kHz = 0x04,
+    Clock286kHz = 0x05,
+    Clock276kHz = 0x06,
+    Clock267kHz = 0x07,
+    Clock258kHz = 0x08,
+    Clock500kHz = 0x09,
+    Clock471kHz = 0x0A,
+    Clock444kHz = 0x0B,
+    Clock421kHz = 0x0C,
+    Clock400kHz = 0x0D,
+    Clock381kHz = 0x0E,
+    Clock364kHz = 0x0F,
+}
+
+// Periodic auxiliary slave channels. Slave 4 is reserved for the one-shot aux_read/aux_write helpers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub enum AuxSlave {
+    Slave0 = 0,
+    Slave1 = 1,
+    Slave2 = 2,
+    Slave3 = 3,
+}
+
+// Transfer performed by an auxiliary slave channel at every sample
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub enum AuxTransfer {
+    // Read `length` (1..=15) bytes starting at `register` into EXT_SENS_DATA
+    Read { register: u8, length: u8 },
+    // Write `value` to `register`
+    Write { register: u8, value: u8 },
+}
+
+// Slave data is only delivered through EXT_SENS_DATA; the FIFO frame decoder does not
+// account for external bytes, so the slave FIFO enables are left off
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub struct AuxSlaveConfig {
+    pub address: u8,          // 7-bit address of the external device
+    pub transfer: AuxTransfer,
+    pub swap_bytes: bool,     // Swap byte pairs, for little-endian external sensors
+}
+
+// Accelerometer wake-up frequency in cycle mode (LP_WAKE_CTRL in PWR_MGMT_2)
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub enum LowPowerWakeRate {
+    Rate1_25Hz = 0,
+    Rate5Hz = 1,
+    Rate20Hz = 2,
+    Rate40Hz = 3,
+}
+
+// Contents of the hardware offset cancellation registers. Accel offsets are 15-bit values in
+// 0.98 mg steps, gyro offsets are in 1/32.8 dps steps, both independent of the configured range.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub struct Offsets {
+    pub accel: [i16; 3],
+    pub gyro: [i16; 3],
+}
+
+// Decoded INT_STATUS. Reading the register clears the latched flags, so a value of this type
+// is a one-shot snapshot: every flag it reports has already been acknowledged on the chip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub struct InterruptStatus {
+    pub free_fall: bool,
+    pub motion: bool,
+    pub zero_motion: bool,
+    pub fifo_overflow: bool,
+    pub i2c_master: bool,
+    pub data_ready: bool,
+}
+
+#[cfg(feature = "mpu6050")]
+impl InterruptStatus {
+    pub fn from_bits(bits: u8) -> Self {
+        InterruptStatus {
+            free_fall: bits & InterruptSource::FreeFall as u8 != 0,
+            motion: bits & InterruptSource::Motion as u8 != 0,
+            zero_motion: bits & InterruptSource::ZeroMotion as u8 != 0,
+            fifo_overflow: bits & InterruptSource::FifoOverflow as u8 != 0,
+            i2c_master: bits & InterruptSource::I2cMaster as u8 != 0,
+            data_ready: bits & InterruptSource::DataReady as u8 != 0,
+        }
     }
 
-    pub fn enter_sleep_mode(&mut self) -> Result<(), Error<E>> {
-        let mut buffer = [0u8];
-        self.i2c.write_read(self.address, &[PWR_MGMT_1], &mut buffer)?;
-        let new_config = buffer[0] | 0x40; // Set SLEEP bit
-        self.i2c.write(self.address, &[PWR_MGMT_1, new_config])?;
-        Ok(())
+    pub fn contains(&self, source: InterruptSource) -> bool {
+        match source {
+            InterruptSource::FreeFall => self.free_fall,
+            InterruptSource::Motion => self.motion,
+            InterruptSource::ZeroMotion => self.zero_motion,
+            InterruptSource::FifoOverflow => self.fifo_overflow,
+            InterruptSource::I2cMaster => self.i2c_master,
+            InterruptSource::DataReady => self.data_ready,
+        }
     }
 
-    pub fn wake_up(&mut self) -> Result<(), Error<E>> {
-        let mut buffer = [0u8];
-        self.i2c.write_read(self.address, &[PWR_MGMT_1], &mut buffer)?;
-        let new_config = buffer[0] & 0xBF; // Clear SLEEP bit
-        self.i2c.write(self.address, &[PWR_MGMT_1, new_config])?;
-        Ok(())
+    pub fn is_empty(&self) -> bool {
+        *self == InterruptStatus::default()
     }
+}
 
-    // Additional MPU6050-specific methods
+// Calibration that can be stored in flash and restored at boot with `apply_calibration()`.
+// Offsets go to the hardware offset registers, scale corrections are per-axis gain factors
+// applied by the driver to scaled readings (1.0 = no correction).
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub struct CalibrationData {
+    pub offsets: Offsets,
+    pub accel_scale_correction: [f32; 3],
+    pub gyro_scale_correction: [f32; 3],
+}
 
-    pub fn disable_sleep(&mut self) -> Result<(), Error<E>> {
-        // Explicitly disable sleep mode - useful during initialization
-        self.i2c.write(self.address, &[PWR_MGMT_1, 0x00])?;
-        Ok(())
+#[cfg(feature = "mpu6050")]
+impl Default for CalibrationData {
+    fn default() -> Self {
+        CalibrationData {
+            offsets: Offsets::default(),
+            accel_scale_correction: [1.0; 3],
+            gyro_scale_correction: [1.0; 3],
+        }
     }
+}
 
-    pub fn enable_temperature_sensor(&mut self) -> Result<(), Error<E>> {
-        let mut buffer = [0u8];
-        self.i2c.write_read(self.address, &[PWR_MGMT_1], &mut buffer)?;
-        let new_config = buffer[0] & 0xF7; // Clear TEMP_DIS bit
-        self.i2c.write(self.address, &[PWR_MGMT_1, new_config])?;
-        Ok(())
+#[cfg(feature = "mpu6050")]
+impl CalibrationData {
+    // Format version, offsets (6 x i16), scale corrections (6 x f32), checksum; little-endian
+    pub const SERIALIZED_SIZE: usize = 1 + 12 + 24 + 1;
+    const FORMAT_VERSION: u8 = 1;
+
+    pub fn to_bytes(&self) -> [u8; Self::SERIALIZED_SIZE] {
+        let mut bytes = [0u8; Self::SERIALIZED_SIZE];
+        bytes[0] = Self::FORMAT_VERSION;
+
+        let offsets = self.offsets.accel.iter().chain(self.offsets.gyro.iter());
+        for (chunk, value) in bytes[1..13].chunks_exact_mut(2).zip(offsets) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+        let scales = self.accel_scale_correction.iter().chain(self.gyro_scale_correction.iter());
+        for (chunk, value) in bytes[13..37].chunks_exact_mut(4).zip(scales) {
+            chunk.copy_from_slice(&value.to_le_bytes());
+        }
+
+        bytes[37] = checksum(&bytes[..37]);
+        bytes
     }
 
-    pub fn disable_temperature_sensor(&mut self) -> Result<(), Error<E>> {
+    // None if the buffer is too short, has an unknown version, a bad checksum or non-finite scales
+    pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        let bytes = bytes.get(..Self::SERIALIZED_SIZE)?;
+        if bytes[0] != Self::FORMAT_VERSION || bytes[37] != checksum(&bytes[..37]) {
+            return None;
+        }
+
+        let word = |index: usize| i16::from_le_bytes([bytes[1 + index * 2], bytes[2 + index * 2]]);
+        let float = |index: usize| {
+            let start = 13 + index * 4;
+            f32::from_le_bytes([bytes[start], bytes[start + 1], bytes[start + 2], bytes[start + 3]])
+        };
+
+        let data = CalibrationData {
+            offsets: Offsets {
+                accel: [word(0), word(1), word(2)],
+                gyro: [word(3), word(4), word(5)],
+            },
+            accel_scale_correction: [float(0), float(1), float(2)],
+            gyro_scale_correction: [float(3), float(4), float(5)],
+        };
+
+        let scales = data.accel_scale_correction.iter().chain(data.gyro_scale_correction.iter());
+        if scales.into_iter().any(|scale| !scale.is_finite()) {
+            return None;
+        }
+        Some(data)
+    }
+}
+
+// Sum of all bytes, inverted so an erased (all 0xFF or all 0x00) flash page never validates
+#[cfg(feature = "mpu6050")]
+fn checksum(bytes: &[u8]) -> u8 {
+    !bytes.iter().fold(0u8, |sum, &byte| sum.wrapping_add(byte))
+}
+
+// Per-axis self-test outcome. Deviations are the change of the measured self-test response
+// from the factory trim value, in percent; an axis passes within +/-14%.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub struct SelfTestResult {
+    pub accel_passed: [bool; 3],
+    pub gyro_passed: [bool; 3],
+    pub accel_deviation: [f32; 3],
+    pub gyro_deviation: [f32; 3],
+}
+
+#[cfg(feature = "mpu6050")]
+impl SelfTestResult {
+    pub const LIMIT_PERCENT: f32 = 14.0;
+
+    pub fn passed(&self) -> bool {
+        self.accel_passed.iter().chain(self.gyro_passed.iter()).all(|&passed| passed)
+    }
+}
+
+// Axis and polarity that triggered the last motion interrupt, from MOT_DETECT_STATUS
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub struct MotionStatus {
+    pub x_negative: bool,
+    pub x_positive: bool,
+    pub y_negative: bool,
+    pub y_positive: bool,
+    pub z_negative: bool,
+    pub z_positive: bool,
+    pub zero_motion: bool,
+}
+
+#[cfg(feature = "mpu6050")]
+impl MotionStatus {
+    pub fn from_bits(bits: u8) -> Self {
+        MotionStatus {
+            x_negative: bits & 0x80 != 0,
+            x_positive: bits & 0x40 != 0,
+            y_negative: bits & 0x20 != 0,
+            y_positive: bits & 0x10 != 0,
+            z_negative: bits & 0x08 != 0,
+            z_positive: bits & 0x04 != 0,
+            zero_motion: bits & 0x01 != 0,
+        }
+    }
+
+    pub fn any_axis(&self) -> bool {
+        self.x_negative || self.x_positive || self.y_negative || self.y_positive || self.z_negative || self.z_positive
+    }
+}
+
+// One FIFO frame decoded according to the FifoConfig it was captured with
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu6050")))]
+pub struct FifoSample {
+    pub accel: Option<[i16; 3]>,
+    pub temp: Option<i16>,
+    pub gyro: [Option<i16>; 3],
+}
+
+// Orientation computed by the DMP, decoded from the Q30 quaternion at the start of each packet
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "dmp")))]
+#[cfg(all(feature = "mpu6050", feature = "dmp"))]
+pub struct Quaternion {
+    pub w: f32,
+    pub x: f32,
+    pub y: f32,
+    pub z: f32,
+}
+
+#[cfg(all(feature = "mpu6050", feature = "dmp"))]
+impl Quaternion {
+    // Decode four big-endian Q30 words (w, x, y, z)
+    pub fn from_q30_bytes(bytes: &[u8; 16]) -> Self {
+        let word = |offset: usize| {
+            i32::from_be_bytes([bytes[offset], bytes[offset + 1], bytes[offset + 2], bytes[offset + 3]]) as f32
+                / 1073741824.0   // 2^30
+        };
+        Quaternion {
+            w: word(0),
+            x: word(4),
+            y: word(8),
+            z: word(12),
+        }
+    }
+
+    // Direction of gravity in the sensor frame, in g. The DMP packet carries gravity only
+    // implicitly, as the rotation it fused; this rotates the world Z axis into the sensor frame.
+    pub fn gravity(&self) -> [f32; 3] {
+        [
+            2.0 * (self.x * self.z - self.w * self.y),
+            2.0 * (self.w * self.x + self.y * self.z),
+            self.w * self.w - self.x * self.x - self.y * self.y + self.z * self.z,
+        ]
+    }
+
+    // Acceleration with gravity removed, in g. `acceleration` must come from the same DMP
+    // packet as the quaternion, otherwise motion between the two readings leaks into the result.
+    pub fn linear_acceleration(&self, acceleration: [f32; 3]) -> [f32; 3] {
+        let gravity = self.gravity();
+        [
+            acceleration[0] - gravity[0],
+            acceleration[1] - gravity[1],
+            acceleration[2] - gravity[2],
+        ]
+    }
+}
+
+// Quaternion the DMP computes: integrated gyro only (drifts over time), or gyro corrected by the
+// accelerometer so pitch and roll stay anchored to gravity
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "dmp")))]
+#[cfg(all(feature = "mpu6050", feature = "dmp"))]
+pub enum DmpQuaternion {
+    Gyro,
+    GyroAccel,
+}
+
+// FIFO packet produced by the InvenSense motion driver 6.12 DMP image, written by
+// `configure_dmp()`. Every packet starts with the Q30 quaternion, followed by raw accel XYZ and
+// then gyro XYZ (big-endian i16) when enabled, so packets are 16, 22 or 28 bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "dmp")))]
+#[cfg(all(feature = "mpu6050", feature = "dmp"))]
+pub struct DmpConfig {
+    pub quaternion: DmpQuaternion,
+    pub accel: bool,
+    pub gyro: bool,
+    pub gyro_calibration: bool,   // DMP removes gyro bias while at rest; packet gyro is then bias-corrected
+    pub rate_hz: u16,             // Packets per second, 1..=200
+}
+
+#[cfg(all(feature = "mpu6050", feature = "dmp"))]
+impl Default for DmpConfig {
+    fn default() -> Self {
+        DmpConfig {
+            quaternion: DmpQuaternion::GyroAccel,
+            accel: true,
+            gyro: true,
+            gyro_calibration: true,
+            rate_hz: 100,
+        }
+    }
+}
+
+#[cfg(all(feature = "mpu6050", feature = "dmp"))]
+impl DmpConfig {
+    pub fn quaternion(mut self, quaternion: DmpQuaternion) -> Self {
+        self.quaternion = quaternion;
+        self
+    }
+
+    pub fn accel(mut self, enabled: bool) -> Self {
+        self.accel = enabled;
+        self
+    }
+
+    pub fn gyro(mut self, enabled: bool) -> Self {
+        self.gyro = enabled;
+        self
+    }
+
+    pub fn gyro_calibration(mut self, enabled: bool) -> Self {
+        self.gyro_calibration = enabled;
+        self
+    }
+
+    pub fn rate_hz(mut self, hz: u16) -> Self {
+        self.rate_hz = hz;
+        self
+    }
+
+    pub fn packet_size(&self) -> usize {
+        16 + if self.accel { 6 } else { 0 } + if self.gyro { 6 } else { 0 }
+    }
+}
+
+// One DMP packet decoded with the driver's accel and gyro scale. Every field comes from the
+// same packet, so gravity and linear acceleration describe the same instant as the quaternion.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "dmp")))]
+#[cfg(all(feature = "mpu6050", feature = "dmp"))]
+pub struct DmpSample {
+    pub quaternion: Quaternion,
+    pub gravity: [f32; 3],                       // g
+    pub acceleration: Option<[f32; 3]>,          // g, None unless DmpConfig::accel
+    pub linear_acceleration: Option<[f32; 3]>,   // g, None unless DmpConfig::accel
+    pub angular_velocity: Option<[f32; 3]>,      // dps, None unless DmpConfig::gyro
+}
+
+#[cfg(feature = "mpu6050")]
+impl<I2C, E> Mpu6050<I2C>
+where 
+    I2C: I2c<Error = E>
+{
+    // Default I2C address for MPU6050 (AD0 tied low)
+    pub const DEFAULT_ADDRESS: u8 = Address::Ad0Low as u8;
+
+    pub fn new(i2c: I2C, address: u8) -> Self {
+        Mpu6050 {
+            i2c,
+            address,
+            accel_scale: 0.0,
+            gyro_scale: 0.0,
+            accel_range: AccelRange::Range2G,
+            gyro_range: GyroRange::Range250Dps,
+            fifo_config: FifoConfig::default(),
+            variant: None,
+            accel_scale_correction: [1.0; 3],
+            gyro_scale_correction: [1.0; 3],
+            fsync_location: FsyncLocation::Disabled,
+            power_snapshot: None,
+            temperature_enabled: true,
+            #[cfg(feature = "dmp")]
+            dmp_config: None,
+            #[cfg(feature = "dmp")]
+            dmp_packet_size: 0,
+        }
+    }
+
+    // Create the driver, verify the chip and apply `config`
+    pub fn new_with_config(i2c: I2C, address: u8, config: Mpu6050Config, delay: &mut impl DelayNs) -> Result<Self, Error<E>> {
+        let mut sensor = Self::new(i2c, address);
+        sensor.verify_identity()?;
+        sensor.apply_config(&config, delay)?;
+        Ok(sensor)
+    }
+
+    // Reset the chip and program every setting in `config`. The chip ignores writes while the
+    // reset runs, so `reset()` waits it out first. The clock source is selected right after the
+    // reset and the DLPF before the divider, since the divider's base rate depends on it.
+    pub fn apply_config(&mut self, config: &Mpu6050Config, delay: &mut impl DelayNs) -> Result<(), Error<E>> {
+        self.reset(delay)?;
+        self.set_clock_source(config.clock_source)?;
+
+        self.setup_accelerometer(config.accel_range)?;
+        self.setup_gyroscope(config.gyro_range)?;
+        self.set_dlpf_config(config.dlpf)?;
+        self.set_sample_rate(config.sample_rate_divider)?;
+        self.set_fsync_location(config.fsync)?;
+
+        self.configure_interrupt_pin(config.interrupt_pin)?;
+        self.write_reg(INT_ENABLE, config.interrupts)?;
+
+        if config.temperature_enabled {
+            self.enable_temperature_sensor()?;
+        } else {
+            self.disable_temperature_sensor()?;
+        }
+        self.set_axis_standby(config.standby)
+    }
+
+    // Create a new instance with default I2C address
+    pub fn new_default(i2c: I2C) -> Self {
+        Self::new(i2c, Self::DEFAULT_ADDRESS)
+    }
+
+    pub fn new_with_address(i2c: I2C, address: Address) -> Self {
+        Self::new(i2c, address.into())
+    }
+
+    // Find which AD0 address a supported chip answers on, checking AD0 low first. The bus is
+    // only borrowed so it can be handed to `new_with_address()` afterwards.
+    pub fn probe(i2c: &mut I2C) -> Result<Address, Error<E>> {
+        for address in [Address::Ad0Low, Address::Ad0High] {
+            let mut buffer = [0u8];
+            if i2c.write_read(address as u8, &[WHO_AM_I], &mut buffer).is_ok()
+                && ChipVariant::from_who_am_i(buffer[0]).is_some()
+            {
+                return Ok(address);
+            }
+        }
+        Err(Error::NotDetected)
+    }
+
+    // Consume the driver and return the I2C bus
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+
+    fn read_reg(&mut self, register: u8) -> Result<u8, Error<E>> {
+        let mut buffer = [0u8];
+        self.i2c.write_read(self.address, &[register], &mut buffer).map_err(Error::read(register))?;
+        Ok(buffer[0])
+    }
+
+    fn write_reg(&mut self, register: u8, value: u8) -> Result<(), Error<E>> {
+        self.i2c.write(self.address, &[register, value]).map_err(Error::write(register))?;
+        Ok(())
+    }
+
+    // Read-modify-write: replace the bits selected by `mask` with `value`
+    fn update_reg(&mut self, register: u8, mask: u8, value: u8) -> Result<(), Error<E>> {
+        let current = self.read_reg(register)?;
+        self.write_reg(register, (current & !mask) | (value & mask))
+    }
+
+    // Unchecked register access for settings the driver does not wrap yet. Writes bypass the
+    // driver's cached state (ranges, scale factors, FIFO layout), so changing registers it
+    // manages can make later conversions wrong.
+    #[cfg(feature = "raw-registers")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "raw-registers")))]
+    pub fn read_register(&mut self, register: u8) -> Result<u8, Error<E>> {
+        self.read_reg(register)
+    }
+
+    #[cfg(feature = "raw-registers")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "raw-registers")))]
+    pub fn write_register(&mut self, register: u8, value: u8) -> Result<(), Error<E>> {
+        self.write_reg(register, value)
+    }
+
+    // Read the register, pass its value through `f` and write the result back
+    #[cfg(feature = "raw-registers")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "raw-registers")))]
+    pub fn modify_register<F>(&mut self, register: u8, f: F) -> Result<u8, Error<E>>
+    where
+        F: FnOnce(u8) -> u8,
+    {
+        let value = f(self.read_reg(register)?);
+        self.write_reg(register, value)?;
+        Ok(value)
+    }
+
+    pub fn verify_identity(&mut self) -> Result<(), Error<E>> {
+        self.device_info()?;
+        Ok(())
+    }
+
+    // Identify the part on the bus. The result is remembered so variant-specific behaviour
+    // (temperature scaling, offset registers) adapts automatically.
+    pub fn detect_variant(&mut self) -> Result<ChipVariant, Error<E>> {
+        self.device_info()?;
+        self.variant.ok_or(Error::NotDetected)
+    }
+
+    // Variant found by the last `detect_variant()`, `device_info()` or `initialize_sensor()`, if any
+    pub fn variant(&self) -> Option<ChipVariant> {
+        self.variant
+    }
+
+    pub fn device_info(&mut self) -> Result<DeviceInfo, Error<E>> {
+        let mut buffer = [0u8];
+        self.i2c.write_read(self.address, &[WHO_AM_I], &mut buffer).map_err(Error::read(WHO_AM_I))?;
+
+        let variant = ChipVariant::from_who_am_i(buffer[0]).ok_or(Error::NotDetected)?;
+        self.variant = Some(variant);
+
+        Ok(DeviceInfo {
+            device: variant.device(),
+            part_id: buffer[0],
+            revision: None,   // No documented revision register
+            address: self.address,
+        })
+    }
+
+    // Features whose registers only exist on the original MPU6050
+    fn ensure_mpu6050(&self) -> Result<(), Error<E>> {
+        match self.variant {
+            Some(ChipVariant::Mpu6500) | Some(ChipVariant::Mpu6886) => {
+                Err(Error::SensorSpecific("Not supported on this chip variant"))
+            }
+            _ => Ok(()),
+        }
+    }
+
+    fn temperature_from_raw(&self, raw: i16) -> f32 {
+        self.variant.unwrap_or(ChipVariant::Mpu6050).temperature_celsius(raw)
+    }
+
+    fn accel_offset_registers(&self) -> [u8; 3] {
+        match self.variant {
+            Some(ChipVariant::Mpu6500) | Some(ChipVariant::Mpu6886) => {
+                [XA_OFFSET_H_6500, YA_OFFSET_H_6500, ZA_OFFSET_H_6500]
+            }
+            _ => [XA_OFFS_H, YA_OFFS_H, ZA_OFFS_H],
+        }
+    }
+
+    // Wake the device with the X gyro PLL as clock, clearing SLEEP, CYCLE and TEMP_DIS. Use
+    // `reset()` to return the registers to their defaults first.
+    pub fn configure_power(&mut self) -> Result<(), Error<E>> {
+        let config = 0x01; // Clock source: PLL with X axis gyroscope reference
+        self.i2c.write(self.address, &[PWR_MGMT_1, config]).map_err(Error::write(PWR_MGMT_1))?;
+        self.temperature_enabled = true;
+        Ok(())
+    }
+
+    pub fn set_clock_source(&mut self, source: ClockSource) -> Result<(), Error<E>> {
+        self.update_reg(PWR_MGMT_1, 0x07, source as u8)
+    }
+
+    pub fn get_clock_source(&mut self) -> Result<ClockSource, Error<E>> {
+        Ok(Self::clock_source_from_bits(self.read_reg(PWR_MGMT_1)?))
+    }
+
+    fn clock_source_from_bits(bits: u8) -> ClockSource {
+        match bits & 0x07 {
+            0x01 => ClockSource::PllGyroX,
+            0x02 => ClockSource::PllGyroY,
+            0x03 => ClockSource::PllGyroZ,
+            0x04 => ClockSource::PllExternal32kHz,
+            0x05 => ClockSource::PllExternal19MHz,
+            0x07 => ClockSource::Stop,
+            _ => ClockSource::Internal,   // 6 is reserved
+        }
+    }
+
+    pub fn get_power_config(&mut self) -> Result<PowerConfig, Error<E>> {
+        let mut buffer = [0u8; 2];
+        self.i2c.write_read(self.address, &[PWR_MGMT_1], &mut buffer).map_err(Error::read(PWR_MGMT_1))?;   // PWR_MGMT_1, PWR_MGMT_2
+
+        let wake_rate = match buffer[1] >> 6 {
+            0x00 => LowPowerWakeRate::Rate1_25Hz,
+            0x01 => LowPowerWakeRate::Rate5Hz,
+            0x02 => LowPowerWakeRate::Rate20Hz,
+            _ => LowPowerWakeRate::Rate40Hz,
+        };
+
+        Ok(PowerConfig {
+            clock_source: Self::clock_source_from_bits(buffer[0]),
+            sleep: buffer[0] & 0x40 != 0,
+            cycle: buffer[0] & 0x20 != 0,
+            temperature_disabled: buffer[0] & 0x08 != 0,
+            wake_rate,
+            standby: AxisStandby::from_bits(buffer[1]),
+        })
+    }
+
+    // Reset the gyro, accel and temperature analog and digital signal paths. Configuration is
+    // kept; use this to unstick a sensor whose outputs froze after a bus glitch.
+    pub fn reset_signal_paths(&mut self) -> Result<(), Error<E>> {
+        self.write_reg(SIGNAL_PATH_RESET, 0x07)
+    }
+
+    // Return every register to its power-on default: set DEVICE_RESET, wait for the reset to
+    // complete and wake the device on the X gyro PLL. The chip comes back at 2 g / 250 dps, so the
+    // cached scale factors are updated to match; FIFO streaming is stopped.
+    pub fn reset(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<E>> {
+        self.write_reg(PWR_MGMT_1, 0x80)?;
+        delay.delay_ms(100);
+
+        self.write_reg(PWR_MGMT_1, 0x01)?;
+        delay.delay_ms(10);   // Allow the PLL to lock
+
+        self.accel_scale = 2.0 / 32768.0;
+        self.gyro_scale = 250.0 / 32768.0;
+        self.accel_range = AccelRange::Range2G;
+        self.gyro_range = GyroRange::Range250Dps;
+        self.fifo_config = FifoConfig::default();
+        self.fsync_location = FsyncLocation::Disabled;
+        self.power_snapshot = None;
+        self.temperature_enabled = true;
+        #[cfg(feature = "dmp")]
+        {
+            self.dmp_config = None;
+            self.dmp_packet_size = 0;
+        }
+        Ok(())
+    }
+
+    pub fn setup_accelerometer(&mut self, range: AccelRange) -> Result<(), Error<E>> {
+        let (config_value, scale) = match range {
+            AccelRange::Range2G => (0x00, 2.0 / 32768.0),
+            AccelRange::Range4G => (0x08, 4.0 / 32768.0),
+            AccelRange::Range8G => (0x10, 8.0 / 32768.0),
+            AccelRange::Range16G => (0x18, 16.0 / 32768.0),
+        };
+        self.i2c.write(self.address, &[ACCEL_CONFIG, config_value]).map_err(Error::write(ACCEL_CONFIG))?;
+        self.accel_scale = scale;
+        self.accel_range = range;
+        Ok(())
+    }
+
+    pub fn setup_gyroscope(&mut self, range: GyroRange) -> Result<(), Error<E>> {
+        let (config_value, scale) = match range {
+            GyroRange::Range250Dps => (0x00, 250.0 / 32768.0),
+            GyroRange::Range500Dps => (0x08, 500.0 / 32768.0),
+            GyroRange::Range1000Dps => (0x10, 1000.0 / 32768.0),
+            GyroRange::Range2000Dps => (0x18, 2000.0 / 32768.0),
+        };
+        self.i2c.write(self.address, &[GYRO_CONFIG, config_value]).map_err(Error::write(GYRO_CONFIG))?;
+        self.gyro_scale = scale;
+        self.gyro_range = range;
+        Ok(())
+    }
+
+    // Range currently held by the chip, read back from ACCEL_CONFIG
+    pub fn get_accel_range(&mut self) -> Result<AccelRange, Error<E>> {
+        match (self.read_reg(ACCEL_CONFIG)? >> 3) & 0x03 {
+            0x00 => Ok(AccelRange::Range2G),
+            0x01 => Ok(AccelRange::Range4G),
+            0x02 => Ok(AccelRange::Range8G),
+            _ => Ok(AccelRange::Range16G),
+        }
+    }
+
+    // Range currently held by the chip, read back from GYRO_CONFIG
+    pub fn get_gyro_range(&mut self) -> Result<GyroRange, Error<E>> {
+        match (self.read_reg(GYRO_CONFIG)? >> 3) & 0x03 {
+            0x00 => Ok(GyroRange::Range250Dps),
+            0x01 => Ok(GyroRange::Range500Dps),
+            0x02 => Ok(GyroRange::Range1000Dps),
+            _ => Ok(GyroRange::Range2000Dps),
+        }
+    }
+
+    // Compare the ranges held by the chip with the ones the cached scale factors were computed
+    // for. A mismatch means the device reset behind the driver's back.
+    pub fn verify_configuration(&mut self) -> Result<bool, Error<E>> {
+        let (accel_range, gyro_range) = self.configured_ranges();
+        Ok(self.get_accel_range()? == accel_range && self.get_gyro_range()? == gyro_range)
+    }
+
+    pub fn initialize_sensor(&mut self, accel_range: AccelRange, gyro_range: GyroRange) -> Result<(), Error<E>> {
+        self.verify_identity()?;
+        self.configure_power()?;
+        self.setup_accelerometer(accel_range)?;
+        self.setup_gyroscope(gyro_range)?;
+        Ok(())
+    }
+
+    pub fn read_accel_raw(&mut self) -> Result<[i16; 3], Error<E>> {
+        let mut buffer = [0u8; 6];
+        self.i2c.write_read(self.address, &[ACCEL_XOUT_H], &mut buffer).map_err(Error::read(ACCEL_XOUT_H))?;
+        let x = ((buffer[0] as i16) << 8) | buffer[1] as i16;
+        let y = ((buffer[2] as i16) << 8) | buffer[3] as i16;
+        let z = ((buffer[4] as i16) << 8) | buffer[5] as i16;
+        Ok([x, y, z])
+    }
+
+    pub fn read_gyro_raw(&mut self) -> Result<[i16; 3], Error<E>> {
+        let mut buffer = [0u8; 6];
+        self.i2c.write_read(self.address, &[GYRO_XOUT_H], &mut buffer).map_err(Error::read(GYRO_XOUT_H))?;
+        let x = ((buffer[0] as i16) << 8) | buffer[1] as i16;
+        let y = ((buffer[2] as i16) << 8) | buffer[3] as i16;
+        let z = ((buffer[4] as i16) << 8) | buffer[5] as i16;
+        Ok([x, y, z])
+    }
+
+    pub fn read_temp_raw(&mut self) -> Result<i16, Error<E>> {
+        let mut buffer = [0u8; 2];
+        self.i2c.write_read(self.address, &[TEMP_OUT_H], &mut buffer).map_err(Error::read(TEMP_OUT_H))?;
+        let temp = ((buffer[0] as i16) << 8) | buffer[1] as i16;
+        Ok(temp)
+    }
+
+    pub fn read_acceleration(&mut self) -> Result<[f32; 3], Error<E>> {
+        let raw = self.read_accel_raw()?;
+        Ok(self.scale_acceleration(raw))
+    }
+
+    pub fn read_angular_velocity(&mut self) -> Result<[f32; 3], Error<E>> {
+        let raw = self.read_gyro_raw()?;
+        Ok(self.scale_angular_velocity(raw))
+    }
+
+    fn scale_acceleration(&self, raw: [i16; 3]) -> [f32; 3] {
+        let correction = self.accel_scale_correction;
+        [
+            raw[0] as f32 * self.accel_scale * correction[0],
+            raw[1] as f32 * self.accel_scale * correction[1],
+            raw[2] as f32 * self.accel_scale * correction[2],
+        ]
+    }
+
+    fn scale_angular_velocity(&self, raw: [i16; 3]) -> [f32; 3] {
+        let correction = self.gyro_scale_correction;
+        [
+            raw[0] as f32 * self.gyro_scale * correction[0],
+            raw[1] as f32 * self.gyro_scale * correction[1],
+            raw[2] as f32 * self.gyro_scale * correction[2],
+        ]
+    }
+
+    // Ranges the cached scale factors were computed for, Range2G/Range250Dps if never configured
+    fn configured_ranges(&self) -> (AccelRange, GyroRange) {
+        (self.accel_range, self.gyro_range)
+    }
+
+    // Integer-only conversions for targets without an FPU. Full scale is 2^k g (or 250 * 2^k dps),
+    // so the scaling reduces to a multiply and a divide by a power of two. Scale corrections from
+    // `apply_calibration()` are not applied; hardware offsets are, since the chip removes them.
+    #[cfg_attr(docsrs, doc(cfg(feature = "fixed-point")))]
+    #[cfg(feature = "fixed-point")]
+    pub fn read_acceleration_mg(&mut self) -> Result<[i32; 3], Error<E>> {
+        let raw = self.read_accel_raw()?;
+        let multiplier: i32 = match self.accel_range {
+            AccelRange::Range2G => 1,
+            AccelRange::Range4G => 2,
+            AccelRange::Range8G => 4,
+            AccelRange::Range16G => 8,
+        };
+        // mg = raw * 2000 * multiplier / 32768
+        Ok(raw.map(|value| (value as i32 * multiplier * 125) / 2048))
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "fixed-point")))]
+    #[cfg(feature = "fixed-point")]
+    pub fn read_angular_velocity_mdps(&mut self) -> Result<[i32; 3], Error<E>> {
+        let raw = self.read_gyro_raw()?;
+        let multiplier: i64 = match self.gyro_range {
+            GyroRange::Range250Dps => 1,
+            GyroRange::Range500Dps => 2,
+            GyroRange::Range1000Dps => 4,
+            GyroRange::Range2000Dps => 8,
+        };
+        // mdps = raw * 250000 * multiplier / 32768, which overflows i32 at 2000 dps
+        Ok(raw.map(|value| ((value as i64 * multiplier * 15625) / 2048) as i32))
+    }
+
+    // Plausibility limits matching the currently configured ranges
+    pub fn validation_limits(&self) -> Limits {
+        Limits::for_imu(self.accel_scale * 32768.0, self.gyro_scale * 32768.0)
+    }
+
+    // Accel, temperature and gyro registers are contiguous (0x3B-0x48): read them in a single
+    // 14-byte transaction so all channels belong to the same sample
+    pub fn read_all_raw(&mut self) -> Result<RawSample, Error<E>> {
+        let mut buffer = [0u8; IMU_FRAME_SIZE];
+        self.i2c.write_read(self.address, &[ACCEL_XOUT_H], &mut buffer).map_err(Error::read(ACCEL_XOUT_H))?;
+
+        let frame = ImuFrameView::new(&buffer).ok_or(Error::InvalidData)?;
+        Ok(RawSample {
+            accel: frame.accel_raw(),
+            temp: frame.temp_raw(),
+            gyro: frame.gyro_raw(),
+        })
+    }
+
+    pub fn read_sample(&mut self) -> Result<Sample, Error<E>> {
+        let raw = self.read_all_raw()?;
+        Ok(Sample {
+            acceleration: self.scale_acceleration(raw.accel),
+            angular_velocity: self.scale_angular_velocity(raw.gyro),
+            temperature: self.temperature_enabled.then(|| self.temperature_from_raw(raw.temp)),
+            timestamp: None,
+        })
+    }
+
+    // Poll the data-ready flag until a new sample is available and return it, or fail with
+    // `Error::Timeout` after `timeout_us`. Enables the data-ready interrupt source if needed.
+    pub fn read_when_ready(&mut self, delay: &mut impl DelayNs, timeout_us: u32) -> Result<Sample, Error<E>> {
+        const POLL_INTERVAL_US: u32 = 100;
+
+        self.enable_interrupt(InterruptSource::DataReady)?;
+
+        let mut waited_us = 0u32;
+        loop {
+            if self.read_interrupt_status()?.data_ready {
+                return self.read_sample();
+            }
+            if waited_us >= timeout_us {
+                return Err(Error::Timeout);
+            }
+
+            let step = POLL_INTERVAL_US.min(timeout_us - waited_us);
+            delay.delay_us(step);
+            waited_us += step;
+        }
+    }
+
+    // None when TEMP_DIS is set: TEMP_OUT then holds the last value converted before the sensor
+    // was disabled, not the current die temperature.
+    pub fn read_temperature_celsius(&mut self) -> Result<Option<f32>, Error<E>> {
+        if !self.temperature_enabled {
+            return Ok(None);
+        }
+        let raw = self.read_temp_raw()?;
+        // MPU6050: TEMP_OUT / 340 + 36.53, see ChipVariant::temperature_celsius for the other parts
+        let temperature = self.temperature_from_raw(raw);
+        Ok(Some(temperature))
+    }
+
+    pub fn set_sample_rate(&mut self, divider: u8) -> Result<(), Error<E>> {
+        // Sample Rate = Gyroscope Output Rate / (1 + SMPLRT_DIV)
+        // Gyroscope Output Rate = 8kHz when the DLPF is disabled (DLPF_CFG = 0 or 7), and 1kHz when the DLPF is enabled
+        self.i2c.write(self.address, &[SMPRT_DIV, divider]).map_err(Error::write(SMPRT_DIV))?;
+        Ok(())
+    }
+
+    pub fn get_sample_rate_divider(&mut self) -> Result<u8, Error<E>> {
+        self.read_reg(SMPRT_DIV)
+    }
+
+    // Pick DLPF and divider for the requested rate and return the rate actually achieved.
+    // Above 1 kHz the DLPF is switched off so the divider runs from the 8 kHz gyro clock; the
+    // accelerometer still updates at 1 kHz, so those extra samples repeat accel values. At or
+    // below 1 kHz the widest bandwidth under the Nyquist limit is chosen.
+    pub fn set_output_data_rate(&mut self, hz: u16) -> Result<f32, Error<E>> {
+        if !(4..=8000).contains(&hz) {
+            return Err(Error::ConfigError);
+        }
+
+        let (dlpf, base) = if hz > 1000 {
+            (DlpfConfig::Bandwidth260Hz, 8000)
+        } else {
+            let dlpf = match hz / 2 {
+                184.. => DlpfConfig::Bandwidth184Hz,
+                94.. => DlpfConfig::Bandwidth94Hz,
+                44.. => DlpfConfig::Bandwidth44Hz,
+                21.. => DlpfConfig::Bandwidth21Hz,
+                10.. => DlpfConfig::Bandwidth10Hz,
+                _ => DlpfConfig::Bandwidth5Hz,
+            };
+            (dlpf, 1000)
+        };
+
+        let divider = ((base + hz as u32 / 2) / hz as u32 - 1) as u8;
+        self.set_dlpf_config(dlpf)?;
+        self.set_sample_rate(divider)?;
+        self.get_output_data_rate()
+    }
+
+    // Output data rate in Hz as currently configured on the chip, taking the DLPF state into
+    // account: the divider runs from 8 kHz with DLPF_CFG 0 or 7 and from 1 kHz otherwise.
+    pub fn get_output_data_rate(&mut self) -> Result<f32, Error<E>> {
+        let base = match self.read_reg(CONFIG)? & 0x07 {
+            0x00 | 0x07 => 8000.0,
+            _ => 1000.0,
+        };
+        Ok(base / (1.0 + self.get_sample_rate_divider()? as f32))
+    }
+
+    pub fn get_dlpf(&mut self) -> Result<DlpfConfig, Error<E>> {
+        match self.read_reg(CONFIG)? & 0x07 {
+            0x00 => Ok(DlpfConfig::Bandwidth260Hz),
+            0x01 => Ok(DlpfConfig::Bandwidth184Hz),
+            0x02 => Ok(DlpfConfig::Bandwidth94Hz),
+            0x03 => Ok(DlpfConfig::Bandwidth44Hz),
+            0x04 => Ok(DlpfConfig::Bandwidth21Hz),
+            0x05 => Ok(DlpfConfig::Bandwidth10Hz),
+            0x06 => Ok(DlpfConfig::Bandwidth5Hz),
+            _ => Err(Error::InvalidData),   // DLPF_CFG = 7 is reserved
+        }
+    }
+
+    pub fn set_dlpf_config(&mut self, config: DlpfConfig) -> Result<(), Error<E>> {
+        let config_value = match config {
+            DlpfConfig::Bandwidth260Hz => 0x00,
+            DlpfConfig::Bandwidth184Hz => 0x01,
+            DlpfConfig::Bandwidth94Hz => 0x02,
+            DlpfConfig::Bandwidth44Hz => 0x03,
+            DlpfConfig::Bandwidth21Hz => 0x04,
+            DlpfConfig::Bandwidth10Hz => 0x05,
+            DlpfConfig::Bandwidth5Hz => 0x06,
+        };
+        // Keep EXT_SYNC_SET (bits 5:3)
+        self.update_reg(CONFIG, 0x07, config_value)
+    }
+
+    // Latch the FSYNC pin (e.g. a camera strobe) into the LSB of the selected output register
+    pub fn set_fsync_location(&mut self, location: FsyncLocation) -> Result<(), Error<E>> {
+        self.update_reg(CONFIG, 0x38, (location as u8) << 3)?;
+        self.fsync_location = location;
+        Ok(())
+    }
+
+    pub fn fsync_location(&self) -> FsyncLocation {
+        self.fsync_location
+    }
+
+    // Route FSYNC edges to the INT pin. The MPU6050 has no INT_ENABLE bit for FSYNC, the
+    // event is reported through `is_fsync_interrupt_pending()` instead.
+    pub fn configure_fsync_interrupt(&mut self, enable: bool, active_low: bool) -> Result<(), Error<E>> {
+        let value = if enable { 0x04 } else { 0x00 } | if active_low { 0x08 } else { 0x00 };
+        self.update_reg(INT_PIN_CFG, 0x0C, value)
+    }
+
+    // PASS_THROUGH bit of I2C_MST_STATUS, cleared by the read
+    pub fn is_fsync_interrupt_pending(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.read_reg(I2C_MST_STATUS)? & 0x80 != 0)
+    }
+
+    // Read a coherent sample along with the FSYNC flag from the configured location
+    pub fn read_sample_with_fsync(&mut self) -> Result<(RawSample, Option<bool>), Error<E>> {
+        let raw = self.read_all_raw()?;
+        Ok((raw, raw.fsync_flag(self.fsync_location)))
+    }
+
+    pub fn enter_sleep_mode(&mut self) -> Result<(), Error<E>> {
+        let mut buffer = [0u8];
+        self.i2c.write_read(self.address, &[PWR_MGMT_1], &mut buffer).map_err(Error::read(PWR_MGMT_1))?;
+        let new_config = buffer[0] | 0x40; // Set SLEEP bit
+        self.i2c.write(self.address, &[PWR_MGMT_1, new_config]).map_err(Error::write(PWR_MGMT_1))?;
+        Ok(())
+    }
+
+    pub fn wake_up(&mut self) -> Result<(), Error<E>> {
+        let mut buffer = [0u8];
+        self.i2c.write_read(self.address, &[PWR_MGMT_1], &mut buffer).map_err(Error::read(PWR_MGMT_1))?;
+        let new_config = buffer[0] & 0xBF; // Clear SLEEP bit
+        self.i2c.write(self.address, &[PWR_MGMT_1, new_config]).map_err(Error::write(PWR_MGMT_1))?;
+        Ok(())
+    }
+
+    // FIFO buffering. Frames are produced at the sample rate set by SMPLRT_DIV, so at 1 kHz with
+    // all channels enabled the 1024-byte FIFO holds roughly 73 ms of data.
+    pub const FIFO_SIZE: usize = 1024;
+
+    pub fn enable_fifo(&mut self, config: FifoConfig) -> Result<(), Error<E>> {
+        self.update_reg(FIFO_EN, 0xF8, config.to_bits())?;
+        self.fifo_config = config;
+
+        // FIFO_RESET (bit 2) clears stale frames captured with a different layout, FIFO_EN (bit 6) starts it
+        self.update_reg(USER_CTRL, 0x44, 0x44)
+    }
+
+    pub fn disable_fifo(&mut self) -> Result<(), Error<E>> {
+        self.write_reg(FIFO_EN, 0x00)?;
+        self.update_reg(USER_CTRL, 0x40, 0x00)?;
+        self.fifo_config = FifoConfig::default();
+        Ok(())
+    }
+
+    pub fn fifo_config(&self) -> FifoConfig {
+        self.fifo_config
+    }
+
+    pub fn reset_fifo(&mut self) -> Result<(), Error<E>> {
+        // FIFO_RESET self-clears
+        self.update_reg(USER_CTRL, 0x04, 0x04)
+    }
+
+    // Number of bytes currently buffered
+    pub fn fifo_count(&mut self) -> Result<u16, Error<E>> {
+        let mut buffer = [0u8; 2];
+        self.i2c.write_read(self.address, &[FIFO_COUNT_H], &mut buffer).map_err(Error::read(FIFO_COUNT_H))?;
+        Ok((((buffer[0] as u16) << 8) | buffer[1] as u16) & 0x07FF)
+    }
+
+    // True once the FIFO has filled up and started dropping data. After an overflow the frame
+    // boundaries are lost, so the FIFO must be reset before reading again. Reads INT_STATUS,
+    // which also clears the other interrupt flags.
+    pub fn is_fifo_overflowed(&mut self) -> Result<bool, Error<E>> {
+        let status = self.read_interrupt_status()?;
+        Ok(status.fifo_overflow || self.fifo_count()? as usize >= Self::FIFO_SIZE)
+    }
+
+    // Enable the FIFO_OFLOW interrupt on the INT pin
+    pub fn enable_fifo_overflow_interrupt(&mut self) -> Result<(), Error<E>> {
+        self.update_reg(INT_ENABLE, 0x10, 0x10)
+    }
+
+    // Discard the FIFO contents after an overflow and resume streaming with the current
+    // FifoConfig. Returns the number of frames thrown away, which is a lower bound on the gap:
+    // samples the chip dropped while the FIFO was full are not counted anywhere.
+    pub fn recover_fifo_overflow(&mut self) -> Result<usize, Error<E>> {
+        let frame_size = self.fifo_config.frame_size();
+        if frame_size == 0 {
+            return Err(Error::ConfigError);
+        }
+
+        // Stop feeding the FIFO so nothing is written between the count and the reset
+        self.update_reg(FIFO_EN, 0xF8, 0x00)?;
+        let discarded = self.fifo_count()? as usize / frame_size;
+
+        // Re-enabling also pulses FIFO_RESET
+        self.enable_fifo(self.fifo_config)?;
+
+        // Clear the latched FIFO_OFLOW flag
+        self.read_interrupt_status_raw()?;
+        Ok(discarded)
+    }
+
+    // Drain as many whole frames as fit into `buffer` and return the number of bytes read.
+    // Partial frames are left in the FIFO so the next read starts on a frame boundary.
+    pub fn read_fifo(&mut self, buffer: &mut [u8]) -> Result<usize, Error<E>> {
+        let frame_size = self.fifo_config.frame_size();
+        if frame_size == 0 {
+            return Err(Error::ConfigError);
+        }
+
+        let available = self.fifo_count()? as usize;
+        let length = available.min(buffer.len()) / frame_size * frame_size;
+        if length > 0 {
+            self.i2c.write_read(self.address, &[FIFO_R_W], &mut buffer[..length]).map_err(Error::read(FIFO_R_W))?;
+        }
+        Ok(length)
+    }
+
+    // Drain up to `samples.len()` frames and decode them, returning how many were filled in
+    pub fn read_fifo_batch(&mut self, samples: &mut [FifoSample]) -> Result<usize, Error<E>> {
+        let frame_size = self.fifo_config.frame_size();
+        if frame_size == 0 {
+            return Err(Error::ConfigError);
+        }
+
+        let available = self.fifo_count()? as usize / frame_size;
+        let count = available.min(samples.len());
+
+        // Read in chunks of whole frames to keep the number of transactions low
+        let mut chunk = [0u8; 126];
+        let frames_per_chunk = chunk.len() / frame_size;
+        let mut done = 0;
+        while done < count {
+            let frames = frames_per_chunk.min(count - done);
+            let bytes = &mut chunk[..frames * frame_size];
+            self.i2c.write_read(self.address, &[FIFO_R_W], bytes).map_err(Error::read(FIFO_R_W))?;
+
+            for (sample, frame) in samples[done..done + frames].iter_mut().zip(bytes.chunks_exact(frame_size)) {
+                *sample = self.decode_fifo_frame(frame);
+            }
+            done += frames;
+        }
+        Ok(count)
+    }
+
+    // Read up to N frames into a stack-allocated array, returning it with the number of valid entries.
+    // The smallest frame is one 2-byte channel, so N is bounded by half the FIFO size.
+    pub fn read_batch<const N: usize>(&mut self) -> Result<([FifoSample; N], usize), Error<E>> {
+        const { assert!(N <= 512, "MPU6050 FIFO holds at most 512 frames") };
+
+        let mut samples = [FifoSample::default(); N];
+        let count = self.read_fifo_batch(&mut samples)?;
+        Ok((samples, count))
+    }
+
+    fn decode_fifo_frame(&self, frame: &[u8]) -> FifoSample {
+        let config = self.fifo_config;
+        let mut offset = 0;
+        let mut next = || {
+            let value = ((frame[offset] as i16) << 8) | frame[offset + 1] as i16;
+            offset += 2;
+            value
+        };
+
+        let mut sample = FifoSample::default();
+        if config.accel {
+            sample.accel = Some([next(), next(), next()]);
+        }
+        if config.temperature {
+            sample.temp = Some(next());
+        }
+        for (axis, enabled) in [config.gyro_x, config.gyro_y, config.gyro_z].into_iter().enumerate() {
+            if enabled {
+                sample.gyro[axis] = Some(next());
+            }
+        }
+        sample
+    }
+
+    // INT_PIN_CFG bits 7:4; the FSYNC and I2C bypass bits are left untouched
+    pub fn configure_interrupt_pin(&mut self, config: InterruptPinConfig) -> Result<(), Error<E>> {
+        let mut value = 0u8;
+        if config.level == InterruptLevel::ActiveLow {
+            value |= 0x80;
+        }
+        if config.drive == InterruptDrive::OpenDrain {
+            value |= 0x40;
+        }
+        if config.latch == InterruptLatch::UntilCleared {
+            value |= 0x20;
+        }
+        if config.clear_on_any_read {
+            value |= 0x10;
+        }
+        self.update_reg(INT_PIN_CFG, 0xF0, value)
+    }
+
+    pub fn get_interrupt_pin_config(&mut self) -> Result<InterruptPinConfig, Error<E>> {
+        let value = self.read_reg(INT_PIN_CFG)?;
+        Ok(InterruptPinConfig {
+            level: if value & 0x80 != 0 { InterruptLevel::ActiveLow } else { InterruptLevel::ActiveHigh },
+            drive: if value & 0x40 != 0 { InterruptDrive::OpenDrain } else { InterruptDrive::PushPull },
+            latch: if value & 0x20 != 0 { InterruptLatch::UntilCleared } else { InterruptLatch::Pulse50us },
+            clear_on_any_read: value & 0x10 != 0,
+        })
+    }
+
+    pub fn enable_interrupt(&mut self, interrupt: InterruptSource) -> Result<(), Error<E>> {
+        let mask = interrupt as u8;
+        self.update_reg(INT_ENABLE, mask, mask)
+    }
+
+    pub fn disable_interrupt(&mut self, interrupt: InterruptSource) -> Result<(), Error<E>> {
+        self.update_reg(INT_ENABLE, interrupt as u8, 0x00)
+    }
+
+    // Raw INT_STATUS contents. Reading the register clears the pending interrupts.
+    pub fn read_interrupt_status_raw(&mut self) -> Result<u8, Error<E>> {
+        self.read_reg(INT_STATUS)
+    }
+
+    // Read and clear the interrupt flags
+    pub fn read_interrupt_status(&mut self) -> Result<InterruptStatus, Error<E>> {
+        Ok(InterruptStatus::from_bits(self.read_interrupt_status_raw()?))
+    }
+
+    // Raise the motion interrupt when the high-pass filtered acceleration on any axis exceeds
+    // `threshold_mg` (2 mg per LSB, up to 510 mg) for `duration_ms` consecutive samples at the
+    // 1 kHz accelerometer rate. Enables the 5 Hz accel high-pass filter the detector relies on;
+    // calling `setup_accelerometer()` afterwards clears it again.
+    pub fn enable_motion_detection(&mut self, threshold_mg: u16, duration_ms: u8) -> Result<(), Error<E>> {
+        let threshold = (threshold_mg / 2).min(0xFF) as u8;
+        self.write_reg(MOT_THR, threshold)?;
+        self.write_reg(MOT_DUR, duration_ms)?;
+
+        self.update_reg(ACCEL_CONFIG, 0x07, 0x01)?;   // ACCEL_HPF = 5 Hz
+        self.update_reg(MOT_DETECT_CTRL, 0x30, 0x10)?;   // ACCEL_ON_DELAY = 1 ms
+        self.update_reg(INT_ENABLE, 0x40, 0x40)   // MOT_EN
+    }
+
+    pub fn disable_motion_detection(&mut self) -> Result<(), Error<E>> {
+        self.update_reg(INT_ENABLE, 0x40, 0x00)
+    }
+
+    // True if the motion interrupt fired since INT_STATUS was last read. Reading INT_STATUS
+    // clears every interrupt flag.
+    pub fn is_motion_detected(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.read_interrupt_status()?.motion)
+    }
+
+    pub fn read_motion_status(&mut self) -> Result<MotionStatus, Error<E>> {
+        Ok(MotionStatus::from_bits(self.read_reg(MOT_DETECT_STATUS)?))
+    }
+
+    // Raise the free-fall interrupt when the acceleration on all three axes stays below
+    // `threshold_mg` (2 mg per LSB, up to 510 mg) for `duration_ms` (1 ms per LSB). Values around
+    // 300-500 mg and 20-100 ms suit drop detection.
+    pub fn enable_free_fall_detection(&mut self, threshold_mg: u16, duration_ms: u8) -> Result<(), Error<E>> {
+        let threshold = (threshold_mg / 2).min(0xFF) as u8;
+        self.write_reg(FF_THR, threshold)?;
+        self.write_reg(FF_DUR, duration_ms)?;
+
+        self.update_reg(MOT_DETECT_CTRL, 0x0C, 0x04)?;   // FF_COUNT decrement = 1
+        self.update_reg(INT_ENABLE, 0x80, 0x80)   // FF_EN
+    }
+
+    pub fn disable_free_fall_detection(&mut self) -> Result<(), Error<E>> {
+        self.update_reg(INT_ENABLE, 0x80, 0x00)
+    }
+
+    // True if the free-fall interrupt fired since INT_STATUS was last read. Reading INT_STATUS
+    // clears every interrupt flag.
+    pub fn is_free_fall_detected(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.read_interrupt_status()?.free_fall)
+    }
+
+    // Run the factory self-test on the accelerometer and gyroscope. The sensor configuration is
+    // restored afterwards. Takes roughly half a second.
+    pub fn run_self_test(&mut self, delay: &mut impl DelayNs) -> Result<SelfTestResult, Error<E>> {
+        // The MPU6500 family stores self-test codes in a different format
+        self.ensure_mpu6050()?;
+
+        const SAMPLES: i32 = 200;
+        const SAVED: [u8; 4] = [SMPRT_DIV, CONFIG, GYRO_CONFIG, ACCEL_CONFIG];
+
+        let mut saved = [0u8; 4];
+        for (value, register) in saved.iter_mut().zip(SAVED) {
+            *value = self.read_reg(register)?;
+        }
+
+        // 1 kHz sample rate, 44 Hz DLPF, 250 dps and 8 g full scale as mandated by the procedure
+        self.write_reg(SMPRT_DIV, 0x00)?;
+        self.write_reg(CONFIG, 0x03)?;
+        self.write_reg(GYRO_CONFIG, 0x00)?;
+        self.write_reg(ACCEL_CONFIG, 0x10)?;
+        delay.delay_ms(20);
+
+        let (accel_normal, gyro_normal) = self.average_raw(SAMPLES, delay)?;
+
+        // Enable self-test on all axes and let the output settle
+        self.write_reg(ACCEL_CONFIG, 0xF0)?;
+        self.write_reg(GYRO_CONFIG, 0xE0)?;
+        delay.delay_ms(20);
+
+        let (accel_test, gyro_test) = self.average_raw(SAMPLES, delay)?;
+
+        // SELF_TEST_X/Y/Z: XA_TEST[4:2] in bits 7:5, XG_TEST in bits 4:0.
+        // SELF_TEST_A: XA_TEST[1:0] in bits 5:4, YA in 3:2, ZA in 1:0.
+        let mut codes = [0u8; 4];
+        self.i2c.write_read(self.address, &[SELF_TEST_X], &mut codes).map_err(Error::read(SELF_TEST_X))?;
+
+        let mut result = SelfTestResult::default();
+        for axis in 0..3 {
+            let accel_code = ((codes[axis] >> 3) & 0x1C) | ((codes[3] >> (4 - 2 * axis)) & 0x03);
+            let gyro_code = codes[axis] & 0x1F;
+
+            let accel_response = (accel_test[axis] - accel_normal[axis]) as f32;
+            let mut gyro_response = (gyro_test[axis] - gyro_normal[axis]) as f32;
+            if axis == 1 {
+                // The Y gyro trim is defined with a negative sign
+                gyro_response = -gyro_response;
+            }
+
+            result.accel_deviation[axis] = Self::trim_deviation(accel_response, Self::accel_factory_trim(accel_code));
+            result.gyro_deviation[axis] = Self::trim_deviation(gyro_response, Self::gyro_factory_trim(gyro_code));
+            result.accel_passed[axis] = result.accel_deviation[axis].abs() <= SelfTestResult::LIMIT_PERCENT;
+            result.gyro_passed[axis] = result.gyro_deviation[axis].abs() <= SelfTestResult::LIMIT_PERCENT;
+        }
+
+        for (&value, register) in saved.iter().zip(SAVED) {
+            self.write_reg(register, value)?;
+        }
+        delay.delay_ms(20);
+
+        Ok(result)
+    }
+
+    // Factory accel response in LSB at 8 g: 4096 * 0.34 * (0.92 / 0.34)^((code - 1) / 30)
+    fn accel_factory_trim(code: u8) -> f32 {
+        if code == 0 {
+            return 0.0;
+        }
+
+        let mut trim = 4096.0 * 0.34f32;
+        for _ in 1..code {
+            trim *= 1.033_737;   // (0.92 / 0.34)^(1 / 30)
+        }
+        trim
+    }
+
+    // Factory gyro response in LSB at 250 dps: 25 * 131 * 1.046^(code - 1)
+    fn gyro_factory_trim(code: u8) -> f32 {
+        if code == 0 {
+            return 0.0;
+        }
+
+        let mut trim = 25.0 * 131.0f32;
+        for _ in 1..code {
+            trim *= 1.046;
+        }
+        trim
+    }
+
+    // Percent change of the response from the trim; an axis without factory trim always fails
+    fn trim_deviation(response: f32, trim: f32) -> f32 {
+        if trim == 0.0 {
+            return f32::INFINITY;
+        }
+        (response - trim) / trim * 100.0
+    }
+
+    fn average_raw(&mut self, samples: i32, delay: &mut impl DelayNs) -> Result<([i32; 3], [i32; 3]), Error<E>> {
+        mpu_common::average_raw(samples, delay, || Ok((self.read_accel_raw()?, self.read_gyro_raw()?)))
+    }
+
+    // Bypass mode connects the auxiliary pins (XDA/XCL) directly to the host bus, so devices
+    // wired there (e.g. the HMC5883L on GY-86/GY-87 boards) can be driven by their own drivers
+    pub fn set_i2c_bypass(&mut self, enable: bool) -> Result<(), Error<E>> {
+        if enable {
+            self.update_reg(USER_CTRL, 0x20, 0x00)?;   // The I2C master must be off in bypass
+            self.update_reg(INT_PIN_CFG, 0x02, 0x02)
+        } else {
+            self.update_reg(INT_PIN_CFG, 0x02, 0x00)
+        }
+    }
+
+    pub fn is_i2c_bypass_enabled(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.read_reg(INT_PIN_CFG)? & 0x02 != 0)
+    }
+
+    // Let the MPU6050 act as I2C master on the auxiliary bus and poll the configured slaves
+    // at every sample
+    pub fn enable_i2c_master(&mut self, clock: I2cMasterClock) -> Result<(), Error<E>> {
+        self.update_reg(INT_PIN_CFG, 0x02, 0x00)?;   // Clear I2C_BYPASS_EN
+        self.update_reg(I2C_MST_CTRL, 0x0F, clock as u8)?;
+        self.update_reg(USER_CTRL, 0x20, 0x20)         // Set I2C_MST_EN
+    }
+
+    pub fn disable_i2c_master(&mut self) -> Result<(), Error<E>> {
+        self.update_reg(USER_CTRL, 0x20, 0x00)
+    }
+
+    pub fn configure_aux_slave(&mut self, slave: AuxSlave, config: AuxSlaveConfig) -> Result<(), Error<E>> {
+        if config.address > 0x7F {
+            return Err(Error::ConfigError);
+        }
+
+        let base = I2C_SLV0_ADDR + 3 * slave as u8;
+        let swap = if config.swap_bytes { 0x40 } else { 0x00 };
+
+        let (address, register, ctrl) = match config.transfer {
+            AuxTransfer::Read { register, length } => {
+                if length == 0 || length > 15 {
+                    return Err(Error::ConfigError);
+                }
+                (config.address | 0x80, register, 0x80 | swap | length)
+            }
+            AuxTransfer::Write { register, value } => {
+                self.write_reg(I2C_SLV0_DO + slave as u8, value)?;
+                (config.address, register, 0x80 | 0x01)
+            }
+        };
+
+        // Keep the slave disabled while its address and register are changed
+        self.write_reg(base + 2, 0x00)?;
+        self.write_reg(base, address)?;
+        self.write_reg(base + 1, register)?;
+        self.write_reg(base + 2, ctrl)
+    }
+
+    pub fn disable_aux_slave(&mut self, slave: AuxSlave) -> Result<(), Error<E>> {
+        self.write_reg(I2C_SLV0_ADDR + 3 * slave as u8 + 2, 0x00)
+    }
+
+    // Copy data gathered by the auxiliary slaves. Reads are stored back to back in slave order,
+    // at most 24 bytes.
+    pub fn read_external_sensor_data(&mut self, buffer: &mut [u8]) -> Result<usize, Error<E>> {
+        let length = buffer.len().min(24);
+        self.i2c.write_read(self.address, &[EXT_SENS_DATA_00], &mut buffer[..length]).map_err(Error::read(EXT_SENS_DATA_00))?;
+        Ok(length)
+    }
+
+    // One-shot register write to an external device through slave 4
+    pub fn aux_write(&mut self, address: u8, register: u8, value: u8) -> Result<(), Error<E>> {
+        self.write_reg(I2C_SLV4_ADDR, address & 0x7F)?;
+        self.write_reg(I2C_SLV4_REG, register)?;
+        self.write_reg(I2C_SLV4_DO, value)?;
+        self.write_reg(I2C_SLV4_CTRL, 0x80)?;
+        self.wait_slave4_done()
+    }
+
+    // One-shot register read from an external device through slave 4
+    pub fn aux_read(&mut self, address: u8, register: u8) -> Result<u8, Error<E>> {
+        self.write_reg(I2C_SLV4_ADDR, 0x80 | (address & 0x7F))?;
+        self.write_reg(I2C_SLV4_REG, register)?;
+        self.write_reg(I2C_SLV4_CTRL, 0x80)?;
+        self.wait_slave4_done()?;
+        self.read_reg(I2C_SLV4_DI)
+    }
+
+    fn wait_slave4_done(&mut self) -> Result<(), Error<E>> {
+        mpu_common::wait_slave4_done(|| self.read_reg(I2C_MST_STATUS))
+    }
+
+    // Disable individual accelerometer/gyroscope axes. Standby axes read as zero. The default
+    // clock uses the X gyro as PLL reference, so switch to the internal oscillator before
+    // putting that axis into standby.
+    pub fn set_axis_standby(&mut self, standby: AxisStandby) -> Result<(), Error<E>> {
+        // LP_WAKE_CTRL (bits 7:6) is preserved
+        self.update_reg(PWR_MGMT_2, 0x3F, standby.to_bits())
+    }
+
+    pub fn get_axis_standby(&mut self) -> Result<AxisStandby, Error<E>> {
+        Ok(AxisStandby::from_bits(self.read_reg(PWR_MGMT_2)?))
+    }
+
+    // Accel-only low-power cycle mode: the chip sleeps and wakes at `rate` to take a single
+    // accelerometer sample. Gyros go to standby and the temperature sensor is disabled.
+    pub fn enter_cycle_mode(&mut self, rate: LowPowerWakeRate) -> Result<(), Error<E>> {
+        // LP_WAKE_CTRL does not exist on the MPU6500 family (LP_ACCEL_ODR replaces it)
+        self.ensure_mpu6050()?;
+
+        // LP_WAKE_CTRL (bits 7:6) and STBY_XG/YG/ZG (bits 2:0)
+        self.update_reg(PWR_MGMT_2, 0xC7, ((rate as u8) << 6) | 0x07)?;
+        // CYCLE (bit 5) and TEMP_DIS (bit 3) set, SLEEP (bit 6) cleared
+        self.update_reg(PWR_MGMT_1, 0x68, 0x28)?;
+        self.temperature_enabled = false;
+        Ok(())
+    }
+
+    pub fn set_cycle_wake_rate(&mut self, rate: LowPowerWakeRate) -> Result<(), Error<E>> {
+        self.update_reg(PWR_MGMT_2, 0xC0, (rate as u8) << 6)
+    }
+
+    // Back to continuous measurement with gyros and temperature sensor enabled
+    pub fn exit_cycle_mode(&mut self) -> Result<(), Error<E>> {
+        self.update_reg(PWR_MGMT_1, 0x28, 0x00)?;
+        self.temperature_enabled = true;
+        self.update_reg(PWR_MGMT_2, 0x07, 0x00)
+    }
+
+    pub fn is_cycle_mode_enabled(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.read_reg(PWR_MGMT_1)? & 0x20 != 0)
+    }
+
+    // Lowest-current state that still produces data: gyros in standby, temperature sensor off,
+    // DLPF off, internal oscillator, and accel-only cycle mode waking at 1.25 Hz. The previous
+    // CONFIG and power registers are saved for `exit_minimum_power()`.
+    pub fn enter_minimum_power(&mut self) -> Result<(), Error<E>> {
+        self.ensure_mpu6050()?;
+
+        // Entering twice must not overwrite the snapshot of the normal configuration
+        if self.power_snapshot.is_none() {
+            let mut power = [0u8; 2];
+            self.i2c.write_read(self.address, &[PWR_MGMT_1], &mut power).map_err(Error::read(PWR_MGMT_1))?;   // PWR_MGMT_1, PWR_MGMT_2
+            self.power_snapshot = Some([self.read_reg(CONFIG)?, power[0], power[1]]);
+        }
+
+        self.update_reg(CONFIG, 0x07, 0x00)?;
+        // The gyro PLL stops with the gyros, so run from the internal 8 MHz oscillator
+        self.update_reg(PWR_MGMT_1, 0x07, ClockSource::Internal as u8)?;
+        self.enter_cycle_mode(LowPowerWakeRate::Rate1_25Hz)
+    }
+
+    // Restore the configuration saved by `enter_minimum_power()`
+    pub fn exit_minimum_power(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<E>> {
+        let [config, power_1, power_2] = self.power_snapshot.ok_or(Error::ConfigError)?;
+
+        // Wake the gyros before switching back to a gyro PLL clock
+        self.write_reg(PWR_MGMT_2, power_2)?;
+        self.write_reg(CONFIG, config)?;
+        self.write_reg(PWR_MGMT_1, power_1)?;
+        self.temperature_enabled = power_1 & 0x08 == 0;
+        delay.delay_ms(10);   // Allow the PLL to lock
+        self.power_snapshot = None;
+        Ok(())
+    }
+
+    pub fn is_minimum_power_enabled(&self) -> bool {
+        self.power_snapshot.is_some()
+    }
+
+    // Gyro offset registers, added to the gyro output in hardware, so a bias is cancelled by its negative
+    pub fn set_gyro_offsets(&mut self, offsets: [i16; 3]) -> Result<(), Error<E>> {
+        let mut buffer = [XG_OFFS_USRH, 0, 0, 0, 0, 0, 0];
+        for (axis, offset) in offsets.iter().enumerate() {
+            let bytes = offset.to_be_bytes();
+            buffer[1 + axis * 2] = bytes[0];
+            buffer[2 + axis * 2] = bytes[1];
+        }
+        self.i2c.write(self.address, &buffer).map_err(Error::write(XG_OFFS_USRH))?;
+        Ok(())
+    }
+
+    pub fn get_gyro_offsets(&mut self) -> Result<[i16; 3], Error<E>> {
+        let mut buffer = [0u8; 6];
+        self.i2c.write_read(self.address, &[XG_OFFS_USRH], &mut buffer).map_err(Error::read(XG_OFFS_USRH))?;
+        let x = ((buffer[0] as i16) << 8) | buffer[1] as i16;
+        let y = ((buffer[2] as i16) << 8) | buffer[3] as i16;
+        let z = ((buffer[4] as i16) << 8) | buffer[5] as i16;
+        Ok([x, y, z])
+    }
+
+    // Accelerometer offset registers, added to the accel output in hardware. The chip loads its
+    // factory trim here at power-on, so adjust the current values rather than writing absolute numbers.
+    pub fn set_accel_offsets(&mut self, offsets: [i16; 3]) -> Result<(), Error<E>> {
+        // The registers hold 15 bits above the reserved bit, reject values that would lose their top bit
+        if offsets.iter().any(|offset| !(ACCEL_OFFSET_MIN..=ACCEL_OFFSET_MAX).contains(offset)) {
+            return Err(Error::ConfigError);
+        }
+
+        for (offset, register) in offsets.iter().zip(self.accel_offset_registers()) {
+            // Bit 0 of the low byte is reserved and must be preserved
+            let mut current = [0u8; 2];
+            self.i2c.write_read(self.address, &[register], &mut current).map_err(Error::read(register))?;
+            let value = ((*offset as u16) << 1) | (current[1] as u16 & 0x01);
+            let bytes = value.to_be_bytes();
+            self.i2c.write(self.address, &[register, bytes[0], bytes[1]]).map_err(Error::write(register))?;
+        }
+        Ok(())
+    }
+
+    pub fn get_accel_offsets(&mut self) -> Result<[i16; 3], Error<E>> {
+        let mut offsets = [0i16; 3];
+        for (offset, register) in offsets.iter_mut().zip(self.accel_offset_registers()) {
+            let mut buffer = [0u8; 2];
+            self.i2c.write_read(self.address, &[register], &mut buffer).map_err(Error::read(register))?;
+            // Arithmetic shift drops the reserved bit and keeps the sign
+            *offset = (((buffer[0] as i16) << 8) | buffer[1] as i16) >> 1;
+        }
+        Ok(offsets)
+    }
+
+    pub fn get_offsets(&mut self) -> Result<Offsets, Error<E>> {
+        Ok(Offsets {
+            accel: self.get_accel_offsets()?,
+            gyro: self.get_gyro_offsets()?,
+        })
+    }
+
+    pub fn set_offsets(&mut self, offsets: Offsets) -> Result<(), Error<E>> {
+        self.set_accel_offsets(offsets.accel)?;
+        self.set_gyro_offsets(offsets.gyro)
+    }
+
+    // Current calibration: offsets read back from the chip plus the driver's scale corrections
+    pub fn get_calibration(&mut self) -> Result<CalibrationData, Error<E>> {
+        Ok(CalibrationData {
+            offsets: self.get_offsets()?,
+            accel_scale_correction: self.accel_scale_correction,
+            gyro_scale_correction: self.gyro_scale_correction,
+        })
+    }
+
+    // Restore a stored calibration, e.g. at boot instead of re-running `calibrate_offsets()`
+    pub fn apply_calibration(&mut self, calibration: &CalibrationData) -> Result<(), Error<E>> {
+        self.set_offsets(calibration.offsets)?;
+        self.accel_scale_correction = calibration.accel_scale_correction;
+        self.gyro_scale_correction = calibration.gyro_scale_correction;
+        Ok(())
+    }
+
+    // Null the stationary bias in hardware. The sensor must be still and level (Z axis up,
+    // reading +1 g). Each pass averages `samples` readings and corrects the offset registers by
+    // the remaining error, stopping once every axis is within one offset step.
+    pub fn calibrate_offsets(&mut self, delay: &mut impl DelayNs, samples: u16) -> Result<Offsets, Error<E>> {
+        const MAX_PASSES: usize = 10;
+
+        if samples == 0 || self.accel_scale == 0.0 || self.gyro_scale == 0.0 {
+            return Err(Error::ConfigError);
+        }
+
+        let mut offsets = self.get_offsets()?;
+        for _ in 0..MAX_PASSES {
+            let (accel_mean, gyro_mean) = self.average_raw(samples as i32, delay)?;
+
+            let mut converged = true;
+            for axis in 0..3 {
+                let gravity = if axis == 2 { 1.0 } else { 0.0 };
+                let accel_error = round_to_i16((accel_mean[axis] as f32 * self.accel_scale - gravity) / 0.00098);
+                let gyro_error = round_to_i16(gyro_mean[axis] as f32 * self.gyro_scale * 32.8);
+
+                if accel_error.abs() > 1 || gyro_error.abs() > 1 {
+                    converged = false;
+                }
+                offsets.accel[axis] = offsets.accel[axis].saturating_sub(accel_error).clamp(ACCEL_OFFSET_MIN, ACCEL_OFFSET_MAX);
+                offsets.gyro[axis] = offsets.gyro[axis].saturating_sub(gyro_error);
+            }
+
+            self.set_offsets(offsets)?;
+            if converged {
+                break;
+            }
+            delay.delay_ms(10);
+        }
+        Ok(offsets)
+    }
+
+    // Digital Motion Processor support. The InvenSense firmware image is not redistributable, so
+    // the application supplies it together with the program start address from the same release.
+    // `configure_dmp()` writes the packet tables of the motion driver 6.12 image (start address
+    // 0x0400); other images are loaded the same way but must be configured by the application.
+    #[cfg_attr(docsrs, doc(cfg(feature = "dmp")))]
+    #[cfg(feature = "dmp")]
+    pub fn write_dmp_memory(&mut self, address: u16, data: &[u8]) -> Result<(), Error<E>> {
+        // Memory is accessed through 256-byte banks; never let a chunk cross a bank boundary
+        const CHUNK_SIZE: usize = 16;
+
+        let mut written = 0;
+        while written < data.len() {
+            let current = address as usize + written;
+            let bank_remaining = 0x100 - (current & 0xFF);
+            let length = CHUNK_SIZE.min(bank_remaining).min(data.len() - written);
+
+            self.write_reg(BANK_SEL, (current >> 8) as u8)?;
+            self.write_reg(MEM_START_ADDR, current as u8)?;
+
+            let mut buffer = [0u8; CHUNK_SIZE + 1];
+            buffer[0] = MEM_R_W;
+            buffer[1..=length].copy_from_slice(&data[written..written + length]);
+            self.i2c.write(self.address, &buffer[..=length]).map_err(Error::write(MEM_R_W))?;
+
+            written += length;
+        }
+        Ok(())
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "dmp")))]
+    #[cfg(feature = "dmp")]
+    pub fn read_dmp_memory(&mut self, address: u16, buffer: &mut [u8]) -> Result<(), Error<E>> {
+        const CHUNK_SIZE: usize = 16;
+
+        let mut read = 0;
+        while read < buffer.len() {
+            let current = address as usize + read;
+            let bank_remaining = 0x100 - (current & 0xFF);
+            let length = CHUNK_SIZE.min(bank_remaining).min(buffer.len() - read);
+
+            self.write_reg(BANK_SEL, (current >> 8) as u8)?;
+            self.write_reg(MEM_START_ADDR, current as u8)?;
+            self.i2c.write_read(self.address, &[MEM_R_W], &mut buffer[read..read + length]).map_err(Error::read(MEM_R_W))?;
+
+            read += length;
+        }
+        Ok(())
+    }
+
+    // Upload the firmware image to DMP memory starting at address 0, verify it and set the
+    // program start address. The DMP stays disabled until `enable_dmp()`.
+    #[cfg_attr(docsrs, doc(cfg(feature = "dmp")))]
+    #[cfg(feature = "dmp")]
+    pub fn load_dmp_firmware(&mut self, firmware: &[u8], start_address: u16) -> Result<(), Error<E>> {
+        self.write_dmp_memory(0x0000, firmware)?;
+
+        let mut readback = [0u8; 16];
+        for (index, expected) in firmware.chunks(readback.len()).enumerate() {
+            let readback = &mut readback[..expected.len()];
+            self.read_dmp_memory((index * 16) as u16, readback)?;
+            if readback != expected {
+                return Err(Error::SensorSpecific("DMP firmware verification failed"));
+            }
+        }
+
+        let start = start_address.to_be_bytes();
+        self.i2c.write(self.address, &[PRGM_START_H, start[0], start[1]]).map_err(Error::write(PRGM_START_H))?;
+        Ok(())
+    }
+
+    // Select what the motion driver 6.12 image puts in each FIFO packet and how often. Call after
+    // `load_dmp_firmware()` and before `enable_dmp()`. The image's integration constants assume
+    // 2000 dps and a 200 Hz sample rate, so the gyro range and output data rate are set to match.
+    #[cfg_attr(docsrs, doc(cfg(feature = "dmp")))]
+    #[cfg(feature = "dmp")]
+    pub fn configure_dmp(&mut self, config: &DmpConfig) -> Result<(), Error<E>> {
+        use dmp_memory::*;
+
+        if !(1..=SAMPLE_RATE).contains(&config.rate_hz) {
+            return Err(Error::ConfigError);
+        }
+
+        self.setup_gyroscope(GyroRange::Range2000Dps)?;
+        self.set_output_data_rate(SAMPLE_RATE)?;
+
+        self.write_dmp_memory(D_0_104, &GYRO_SF.to_be_bytes())?;
+
+        // Raw accel and gyro words appended after the quaternion; 0xA3 is a no-op slot
+        let mut sensors = [0xA3; 10];
+        if config.accel {
+            sensors[1..4].copy_from_slice(&[0xC0, 0xC8, 0xC2]);
+        }
+        if config.gyro {
+            sensors[4..7].copy_from_slice(&[0xC4, 0xCC, 0xC6]);
+        }
+        self.write_dmp_memory(CFG_15, &sensors)?;
+
+        // No tap or orientation gestures, so no 4-byte gesture word in the packet
+        self.write_dmp_memory(CFG_27, &[0xD8])?;
+        self.write_dmp_memory(CFG_20, &[0xD8])?;
+        self.write_dmp_memory(CFG_ANDROID_ORIENT_INT, &[0xD8])?;
+
+        let motion_bias = if config.gyro_calibration {
+            [0xB8, 0xAA, 0xB3, 0x8D, 0xB4, 0x98, 0x0D, 0x35, 0x5D]
+        } else {
+            [0xB8, 0xAA, 0xAA, 0xAA, 0xB0, 0x88, 0xC3, 0xC5, 0xC7]
+        };
+        self.write_dmp_memory(CFG_MOTION_BIAS, &motion_bias)?;
+
+        if config.gyro {
+            let source = if config.gyro_calibration {
+                [0xB2, 0x8B, 0xB6, 0x9B]
+            } else {
+                [0xB0, 0x80, 0xB4, 0x90]
+            };
+            self.write_dmp_memory(CFG_GYRO_RAW_DATA, &source)?;
+        }
+
+        // Exactly one of the two quaternion outputs is enabled
+        let (lp_quat, six_axis_quat) = match config.quaternion {
+            DmpQuaternion::Gyro => ([0xC0, 0xC2, 0xC4, 0xC6], [0xA3; 4]),
+            DmpQuaternion::GyroAccel => ([0x8B; 4], [0x20, 0x28, 0x30, 0x38]),
+        };
+        self.write_dmp_memory(CFG_LP_QUAT, &lp_quat)?;
+        self.write_dmp_memory(CFG_8, &six_axis_quat)?;
+
+        let divider = SAMPLE_RATE / config.rate_hz - 1;
+        self.write_dmp_memory(D_0_22, &divider.to_be_bytes())?;
+        self.write_dmp_memory(CFG_6, &[0xFE, 0xF2, 0xAB, 0xC4, 0xAA, 0xF1, 0xDF, 0xDF, 0xBB, 0xAF, 0xDF, 0xDF])?;
+
+        self.dmp_config = Some(*config);
+        Ok(())
+    }
+
+    // Packet layout last written by `configure_dmp()`, if any
+    #[cfg_attr(docsrs, doc(cfg(feature = "dmp")))]
+    #[cfg(feature = "dmp")]
+    pub fn dmp_config(&self) -> Option<DmpConfig> {
+        self.dmp_config
+    }
+
+    // Start the DMP with the packet layout from `configure_dmp()`
+    #[cfg_attr(docsrs, doc(cfg(feature = "dmp")))]
+    #[cfg(feature = "dmp")]
+    pub fn enable_dmp(&mut self) -> Result<(), Error<E>> {
+        let packet_size = self.dmp_config.ok_or(Error::ConfigError)?.packet_size();
+
+        // The DMP writes its own packets into the FIFO; raw sensor channels would corrupt them
+        self.write_reg(FIFO_EN, 0x00)?;
+        self.fifo_config = FifoConfig::default();
+        self.dmp_packet_size = packet_size;
+
+        // DMP_RESET (bit 3) and FIFO_RESET (bit 2), then DMP_EN (bit 7) and FIFO_EN (bit 6)
+        self.update_reg(USER_CTRL, 0x0C, 0x0C)?;
+        self.update_reg(USER_CTRL, 0xC0, 0xC0)
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "dmp")))]
+    #[cfg(feature = "dmp")]
+    pub fn disable_dmp(&mut self) -> Result<(), Error<E>> {
+        self.update_reg(USER_CTRL, 0x80, 0x00)?;
+        self.dmp_packet_size = 0;
+        Ok(())
+    }
+
+    // Read the next DMP packet into `buffer`, returning false if no complete packet is buffered
+    #[cfg_attr(docsrs, doc(cfg(feature = "dmp")))]
+    #[cfg(feature = "dmp")]
+    pub fn read_dmp_packet(&mut self, buffer: &mut [u8]) -> Result<bool, Error<E>> {
+        let packet_size = self.dmp_packet_size;
+        if packet_size == 0 || buffer.len() < packet_size {
+            return Err(Error::ConfigError);
+        }
+        if (self.fifo_count()? as usize) < packet_size {
+            return Ok(false);
+        }
+
+        self.i2c.write_read(self.address, &[FIFO_R_W], &mut buffer[..packet_size]).map_err(Error::read(FIFO_R_W))?;
+        Ok(true)
+    }
+
+    // Orientation from the next DMP packet, if one is available
+    #[cfg_attr(docsrs, doc(cfg(feature = "dmp")))]
+    #[cfg(feature = "dmp")]
+    pub fn read_dmp_quaternion(&mut self) -> Result<Option<Quaternion>, Error<E>> {
+        let mut packet = [0u8; 64];
+        if !self.read_dmp_packet(&mut packet)? {
+            return Ok(None);
+        }
+
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&packet[..16]);
+        Ok(Some(Quaternion::from_q30_bytes(&bytes)))
+    }
+
+    // Quaternion, gravity, linear acceleration and angular velocity from the next DMP packet, if
+    // one is available. A quaternion far from unit length means the reads lost packet alignment;
+    // the FIFO is reset and InvalidData returned.
+    #[cfg_attr(docsrs, doc(cfg(feature = "dmp")))]
+    #[cfg(feature = "dmp")]
+    pub fn read_dmp_sample(&mut self) -> Result<Option<DmpSample>, Error<E>> {
+        let config = self.dmp_config.ok_or(Error::ConfigError)?;
+        let mut packet = [0u8; 28];
+        if !self.read_dmp_packet(&mut packet)? {
+            return Ok(None);
+        }
+
+        let mut bytes = [0u8; 16];
+        bytes.copy_from_slice(&packet[..16]);
+        let quaternion = Quaternion::from_q30_bytes(&bytes);
+        let norm = quaternion.w * quaternion.w
+            + quaternion.x * quaternion.x
+            + quaternion.y * quaternion.y
+            + quaternion.z * quaternion.z;
+        if !(0.9375..=1.0625).contains(&norm) {
+            self.reset_fifo()?;
+            return Err(Error::InvalidData);
+        }
+
+        let word = |offset: usize| i16::from_be_bytes([packet[offset], packet[offset + 1]]) as f32;
+        let mut offset = 16;
+        let acceleration = if config.accel {
+            let scale = self.accel_scale;
+            offset += 6;
+            Some([word(16) * scale, word(18) * scale, word(20) * scale])
+        } else {
+            None
+        };
+        let angular_velocity = if config.gyro {
+            let scale = self.gyro_scale;
+            Some([word(offset) * scale, word(offset + 2) * scale, word(offset + 4) * scale])
+        } else {
+            None
+        };
+
+        Ok(Some(DmpSample {
+            quaternion,
+            gravity: quaternion.gravity(),
+            acceleration,
+            linear_acceleration: acceleration.map(|accel| quaternion.linear_acceleration(accel)),
+            angular_velocity,
+        }))
+    }
+
+    // Additional MPU6050-specific methods
+
+    pub fn disable_sleep(&mut self) -> Result<(), Error<E>> {
+        // Explicitly disable sleep mode - useful during initialization
+        self.i2c.write(self.address, &[PWR_MGMT_1, 0x00]).map_err(Error::write(PWR_MGMT_1))?;
+        self.temperature_enabled = true;
+        Ok(())
+    }
+
+    pub fn enable_temperature_sensor(&mut self) -> Result<(), Error<E>> {
+        let mut buffer = [0u8];
+        self.i2c.write_read(self.address, &[PWR_MGMT_1], &mut buffer).map_err(Error::read(PWR_MGMT_1))?;
+        let new_config = buffer[0] & 0xF7; // Clear TEMP_DIS bit
+        self.i2c.write(self.address, &[PWR_MGMT_1, new_config]).map_err(Error::write(PWR_MGMT_1))?;
+        self.temperature_enabled = true;
+        Ok(())
+    }
+
+    pub fn disable_temperature_sensor(&mut self) -> Result<(), Error<E>> {
         let mut buffer = [0u8];
-        self.i2c.write_read(self.address, &[PWR_MGMT_1], &mut buffer)?;
+        self.i2c.write_read(self.address, &[PWR_MGMT_1], &mut buffer).map_err(Error::read(PWR_MGMT_1))?;
         let new_config = buffer[0] | 0x08; // Set TEMP_DIS bit
-        self.i2c.write(self.address, &[PWR_MGMT_1, new_config])?;
+        self.i2c.write(self.address, &[PWR_MGMT_1, new_config]).map_err(Error::write(PWR_MGMT_1))?;
+        self.temperature_enabled = false;
+        Ok(())
+    }
+
+    // Reads TEMP_DIS back from the chip and refreshes the driver's copy, e.g. after PWR_MGMT_1
+    // was written behind the driver's back
+    pub fn is_temperature_enabled(&mut self) -> Result<bool, Error<E>> {
+        self.temperature_enabled = self.read_reg(PWR_MGMT_1)? & 0x08 == 0;
+        Ok(self.temperature_enabled)
+    }
+}
+
+#[cfg(feature = "mpu6050")]
+impl<I2C, E> Imu<E> for Mpu6050<I2C>
+where
+    I2C: I2c<Error = E>
+{
+    type AccelRange = AccelRange;
+    type GyroRange = GyroRange;
+
+    fn read_acceleration(&mut self) -> Result<[f32; 3], Error<E>> {
+        Self::read_acceleration(self)
+    }
+
+    fn read_angular_velocity(&mut self) -> Result<[f32; 3], Error<E>> {
+        Self::read_angular_velocity(self)
+    }
+
+    fn read_temperature(&mut self) -> Result<Option<f32>, Error<E>> {
+        self.read_temperature_celsius()
+    }
+
+    fn set_accel_range(&mut self, range: AccelRange) -> Result<(), Error<E>> {
+        self.setup_accelerometer(range)
+    }
+
+    fn set_gyro_range(&mut self, range: GyroRange) -> Result<(), Error<E>> {
+        self.setup_gyroscope(range)
+    }
+
+    fn accel_range(&self) -> AccelRange {
+        self.configured_ranges().0
+    }
+
+    fn gyro_range(&self) -> GyroRange {
+        self.configured_ranges().1
+    }
+}
+
+#[cfg(feature = "mpu6050")]
+impl<I2C, E> Recover<E> for Mpu6050<I2C>
+where
+    I2C: I2c<Error = E>
+{
+    fn recover(&mut self, action: RecoveryAction, delay: &mut impl DelayNs) -> Result<(), Error<E>> {
+        match action {
+            RecoveryAction::SignalPathReset => {
+                self.reset_signal_paths()?;
+            }
+            RecoveryAction::ForceReset => {
+                self.reset(delay)?;
+            }
+            RecoveryAction::Reinitialize => {
+                // `reset()` forgets the ranges, take them first. It also leaves the chip awake on
+                // the PLL, so only the ranges need to be written again.
+                let (accel_range, gyro_range) = self.configured_ranges();
+                self.reset(delay)?;
+                self.verify_identity()?;
+                self.setup_accelerometer(accel_range)?;
+                self.setup_gyroscope(gyro_range)?;
+            }
+        }
         Ok(())
     }
 }
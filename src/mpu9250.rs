@@ -1,13 +1,42 @@
 #[cfg(feature = "mpu9250")]
 use embedded_hal::i2c::I2c;
 
+#[cfg(feature = "mpu9250")]
+use embedded_hal::delay::DelayNs;
+
 #[cfg(feature = "mpu9250")]
 use crate::error::Error;
 
+#[cfg(feature = "mpu9250")]
+use crate::device::{Device, DeviceInfo};
+
+#[cfg(feature = "mpu9250")]
+use crate::validation::Limits;
+
+#[cfg(feature = "mpu9250")]
+use crate::frame::{ImuFrameView, IMU_FRAME_SIZE};
+
+#[cfg(feature = "mpu9250")]
+use crate::mag_calibration::{MagCalibration, MagCalibrator};
+
+#[cfg(feature = "mpu9250")]
+use crate::watchdog::{Recover, RecoveryAction};
+
+#[cfg(feature = "mpu9250")]
+use crate::imu::Imu;
+
+#[cfg(feature = "mpu9250")]
+use crate::mpu_common::{self, ACCEL_OFFSET_MAX, ACCEL_OFFSET_MIN, round_to_i16};
+
 #[cfg(feature = "mpu9250")]
 mod registers {
+   pub const SELF_TEST_X_GYRO: u8 = 0x00;
+   pub const SELF_TEST_X_ACCEL: u8 = 0x0D;
+   pub const XG_OFFSET_H: u8 = 0x13;
+   pub const XA_OFFSET_H: u8 = 0x77;
+   pub const YA_OFFSET_H: u8 = 0x7A;
+   pub const ZA_OFFSET_H: u8 = 0x7D;
    pub const WHO_AM_I: u8 = 0x75;
-   pub const WHO_AM_I_VALUE: u8 = 0x74;
    pub const PWR_MGMT_1: u8 = 0x6B;
    pub const ACCEL_CONFIG: u8 = 0x1C;
    pub const GYRO_CONFIG: u8 = 0x1B;
@@ -16,22 +45,129 @@ mod registers {
    pub const GYRO_XOUT_H: u8 = 0x43;
    pub const SMPRT_DIV: u8 = 0x19;
    pub const CONFIG: u8 = 0x1A;
+   pub const SIGNAL_PATH_RESET: u8 = 0x68;
+   pub const INT_PIN_CFG: u8 = 0x37;
+   pub const INT_ENABLE: u8 = 0x38;
+   pub const INT_STATUS: u8 = 0x3A;
+   pub const USER_CTRL: u8 = 0x6A;
+   pub const PWR_MGMT_2: u8 = 0x6C;
+   pub const ACCEL_CONFIG_2: u8 = 0x1D;
+   pub const LP_ACCEL_ODR: u8 = 0x1E;
+   pub const WOM_THR: u8 = 0x1F;
+   pub const MOT_DETECT_CTRL: u8 = 0x69;
+   pub const FIFO_EN: u8 = 0x23;
+   pub const I2C_MST_CTRL: u8 = 0x24;
+   pub const I2C_SLV0_ADDR: u8 = 0x25;
+   pub const I2C_SLV4_ADDR: u8 = 0x31;
+   pub const I2C_SLV4_REG: u8 = 0x32;
+   pub const I2C_SLV4_DO: u8 = 0x33;
+   pub const I2C_SLV4_CTRL: u8 = 0x34;
+   pub const I2C_SLV4_DI: u8 = 0x35;
+   pub const I2C_MST_STATUS: u8 = 0x36;
+   pub const EXT_SENS_DATA_00: u8 = 0x49;
+   pub const I2C_SLV0_DO: u8 = 0x63;
+   pub const FIFO_R_W: u8 = 0x74;
+
+   // AK8963 magnetometer, reachable on the main bus once I2C bypass is enabled
+   pub const AK8963_ADDRESS: u8 = 0x0C;
+   pub const AK8963_WIA: u8 = 0x00;
+   pub const AK8963_WIA_VALUE: u8 = 0x48;
+   pub const AK8963_HXL: u8 = 0x03;
+   pub const AK8963_CNTL1: u8 = 0x0A;
+   pub const AK8963_ASAX: u8 = 0x10;
 }
 
 
+#[cfg(feature = "mpu9250")]
+use core::marker::PhantomData;
+
 #[cfg(feature = "mpu9250")]
 use registers::*;
 
 #[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
 #[cfg(feature = "mpu9250")]
-pub struct Mpu9250<I2C> {
+pub struct Mpu9250<I2C, State = Configured> {
     i2c: I2C,
     address: u8,
     accel_scale: f32,
     gyro_scale: f32,
+    accel_range: AccelRange,
+    gyro_range: GyroRange,
+    mag_adjustment: [f32; 3],
+    calibration: Calibration,
+    mag_calibration: MagCalibration,
+    gyro_temp_compensation: Option<GyroTempCompensation>,
+    fsync_location: FsyncLocation,
+    variant: Option<ChipVariant>,
+    wake_on_motion_snapshot: Option<[u8; 4]>,   // PWR_MGMT_2, ACCEL_CONFIG_2, INT_ENABLE, MOT_DETECT_CTRL saved by enter_wake_on_motion()
+    _state: PhantomData<State>,
+}
+
+// Typestate markers. `new()` returns an uninitialized driver that only exposes `configure()`,
+// so reads cannot happen before the scale factors are known. `Mpu9250<I2C>` is the configured
+// state that `configure()` returns.
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+#[cfg(feature = "mpu9250")]
+pub struct Uninitialized;
+
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+#[cfg(feature = "mpu9250")]
+pub struct Configured;
+
+// Silicon found behind the MPU9250 API, identified by WHO_AM_I
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub enum ChipVariant {
+    Mpu9250 = 0x71,
+    Mpu9255 = 0x73,
+    Mpu6500 = 0x70,   // No magnetometer
+    Mpu6515 = 0x74,   // No magnetometer, found on some MPU9250-labelled boards
+}
+
+#[cfg(feature = "mpu9250")]
+impl ChipVariant {
+    pub fn from_who_am_i(value: u8) -> Option<Self> {
+        match value {
+            0x71 => Some(ChipVariant::Mpu9250),
+            0x73 => Some(ChipVariant::Mpu9255),
+            0x70 => Some(ChipVariant::Mpu6500),
+            0x74 => Some(ChipVariant::Mpu6515),
+            _ => None,
+        }
+    }
+
+    pub fn who_am_i(&self) -> u8 {
+        *self as u8
+    }
+
+    pub fn has_magnetometer(&self) -> bool {
+        matches!(self, ChipVariant::Mpu9250 | ChipVariant::Mpu9255)
+    }
+
+    pub fn device(&self) -> Device {
+        match self {
+            ChipVariant::Mpu9250 => Device::Mpu9250,
+            ChipVariant::Mpu9255 => Device::Mpu9255,
+            ChipVariant::Mpu6500 => Device::Mpu6500,
+            ChipVariant::Mpu6515 => Device::Mpu6515,
+        }
+    }
+
+    // Degrees Celsius from TEMP_OUT; the whole MPU6500-based family shares one scale
+    pub fn temperature_celsius(&self, raw: i16) -> f32 {
+        match self {
+            ChipVariant::Mpu9250 | ChipVariant::Mpu9255 | ChipVariant::Mpu6500 | ChipVariant::Mpu6515 => {
+                (raw as f32) / 333.87 + 21.0
+            }
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
 pub enum AccelRange {
     Range2G,
@@ -41,6 +177,8 @@ pub enum AccelRange {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
 pub enum GyroRange {
     Range250Dps,
@@ -50,16 +188,442 @@ pub enum GyroRange {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
 pub enum DlpfConfig {
-    Bandwidth260Hz,
+    Bandwidth260Hz,    // 250 Hz on the MPU9250, kept under the MPU6050 name for compatibility
     Bandwidth184Hz,
+    Bandwidth92Hz,
+    Bandwidth41Hz,
+    Bandwidth20Hz,
+    Bandwidth10Hz,
+    Bandwidth5Hz,
+    Bandwidth3600Hz,
+    Bandwidth8800Hz,   // DLPF bypassed (FCHOICE_B), 32 kHz internal rate
+}
+
+// Accelerometer DLPF, configured independently of the gyro in ACCEL_CONFIG_2
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub enum AccelDlpfConfig {
+    Bandwidth1046Hz,   // DLPF bypassed (ACCEL_FCHOICE_B), 4 kHz rate
+    Bandwidth218Hz,
+    Bandwidth99Hz,
+    Bandwidth45Hz,
+    Bandwidth21Hz,
+    Bandwidth10Hz,
+    Bandwidth5Hz,
+    Bandwidth420Hz,
+}
+
+// CLKSEL values in PWR_MGMT_1. Unlike the MPU6050, the MPU9250 cannot lock the PLL to a
+// specific gyro axis: every PLL setting auto-selects the gyro PLL once it is ready and falls
+// back to the internal oscillator otherwise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub enum ClockSource {
+    Internal = 0x00,   // Internal 20 MHz oscillator
+    AutoPll = 0x01,    // Gyro PLL when available, internal oscillator otherwise (default)
+    Stop = 0x07,       // Stops the clock and keeps the timing generator in reset
+}
+
+// Accelerometer wake-up rate used in low-power (cycle) and wake-on-motion modes
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub enum LowPowerOdr {
+    Rate0_24Hz = 0x00,
+    Rate0_49Hz = 0x01,
+    Rate0_98Hz = 0x02,
+    Rate1_95Hz = 0x03,
+    Rate3_91Hz = 0x04,
+    Rate7_81Hz = 0x05,
+    Rate15_63Hz = 0x06,
+    Rate31_25Hz = 0x07,
+    Rate62_50Hz = 0x08,
+    Rate125Hz = 0x09,
+    Rate250Hz = 0x0A,
+    Rate500Hz = 0x0B,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub enum InterruptSource {
+    WakeOnMotion = 0x40,   // Bit 6 of INT_ENABLE
+    FifoOverflow = 0x10,   // Bit 4 of INT_ENABLE
+    Fsync = 0x08,          // Bit 3 of INT_ENABLE
+    DataReady = 0x01,      // Bit 0 of INT_ENABLE
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub enum InterruptLevel {
+    #[default]
+    ActiveHigh,
+    ActiveLow,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub enum InterruptDrive {
+    #[default]
+    PushPull,
+    OpenDrain,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub enum InterruptLatch {
+    #[default]
+    Pulse50us,             // INT pin emits a 50us pulse
+    UntilCleared,          // INT pin held until the interrupt is cleared
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub struct InterruptPinConfig {
+    pub level: InterruptLevel,
+    pub drive: InterruptDrive,
+    pub latch: InterruptLatch,
+    pub clear_on_any_read: bool,   // Clear on any register read instead of only on INT_STATUS reads
 }
 
+// Decoded INT_STATUS. Reading the register clears the latched flags, so a value of this type
+// is a one-shot snapshot: every flag it reports has already been acknowledged on the chip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub struct InterruptStatus {
+    pub wake_on_motion: bool,
+    pub fifo_overflow: bool,
+    pub fsync: bool,
+    pub data_ready: bool,
+}
 
 #[cfg(feature = "mpu9250")]
-impl<I2C, E> Mpu9250<I2C>
-where 
+impl InterruptStatus {
+    pub fn from_bits(bits: u8) -> Self {
+        InterruptStatus {
+            wake_on_motion: bits & InterruptSource::WakeOnMotion as u8 != 0,
+            fifo_overflow: bits & InterruptSource::FifoOverflow as u8 != 0,
+            fsync: bits & InterruptSource::Fsync as u8 != 0,
+            data_ready: bits & InterruptSource::DataReady as u8 != 0,
+        }
+    }
+
+    pub fn contains(&self, source: InterruptSource) -> bool {
+        match source {
+            InterruptSource::WakeOnMotion => self.wake_on_motion,
+            InterruptSource::FifoOverflow => self.fifo_overflow,
+            InterruptSource::Fsync => self.fsync,
+            InterruptSource::DataReady => self.data_ready,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        *self == InterruptStatus::default()
+    }
+}
+
+// Axes to put into standby through PWR_MGMT_2, true disables the axis
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub struct AxisStandby {
+    pub accel_x: bool,
+    pub accel_y: bool,
+    pub accel_z: bool,
+    pub gyro_x: bool,
+    pub gyro_y: bool,
+    pub gyro_z: bool,
+}
+
+#[cfg(feature = "mpu9250")]
+impl AxisStandby {
+    pub fn to_bits(&self) -> u8 {
+        (self.accel_x as u8) << 5
+            | (self.accel_y as u8) << 4
+            | (self.accel_z as u8) << 3
+            | (self.gyro_x as u8) << 2
+            | (self.gyro_y as u8) << 1
+            | (self.gyro_z as u8)
+    }
+
+    pub fn from_bits(bits: u8) -> Self {
+        AxisStandby {
+            accel_x: bits & 0x20 != 0,
+            accel_y: bits & 0x10 != 0,
+            accel_z: bits & 0x08 != 0,
+            gyro_x: bits & 0x04 != 0,
+            gyro_y: bits & 0x02 != 0,
+            gyro_z: bits & 0x01 != 0,
+        }
+    }
+}
+
+// Clock of the auxiliary I2C master (I2C_MST_CLK)
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub enum I2cMasterClock {
+    Clock348kHz = 0x00,
+    Clock333kHz = 0x01,
+    Clock320kHz = 0x02,
+    Clock308kHz = 0x03,
+    Clock296kHz = 0x04,
+    Clock286kHz = 0x05,
+    Clock276kHz = 0x06,
+    Clock267kHz = 0x07,
+    Clock258kHz = 0x08,
+    Clock500kHz = 0x09,
+    Clock471kHz = 0x0A,
+    Clock444kHz = 0x0B,
+    Clock421kHz = 0x0C,
+    Clock400kHz = 0x0D,
+    Clock381kHz = 0x0E,
+    Clock364kHz = 0x0F,
+}
+
+// Periodic auxiliary slave channels. Slave 4 is reserved for the one-shot aux_read/aux_write helpers.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub enum AuxSlave {
+    Slave0 = 0,
+    Slave1 = 1,
+    Slave2 = 2,
+    Slave3 = 3,
+}
+
+// Transfer performed by an auxiliary slave channel at every sample
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub enum AuxTransfer {
+    // Read `length` (1..=15) bytes starting at `register` into EXT_SENS_DATA
+    Read { register: u8, length: u8 },
+    // Write `value` to `register`
+    Write { register: u8, value: u8 },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub struct AuxSlaveConfig {
+    pub address: u8,          // 7-bit address of the external device
+    pub transfer: AuxTransfer,
+    pub swap_bytes: bool,     // Swap byte pairs, for little-endian external sensors
+    pub to_fifo: bool,        // Also push read data into the FIFO
+}
+
+// Per-axis self-test outcome. Ratios are the measured self-test response divided by the
+// factory trim value (0.0 when the chip has no factory trim for that axis).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub struct SelfTestResult {
+    pub accel_passed: [bool; 3],
+    pub gyro_passed: [bool; 3],
+    pub accel_ratio: [f32; 3],
+    pub gyro_ratio: [f32; 3],
+}
+
+#[cfg(feature = "mpu9250")]
+impl SelfTestResult {
+    pub fn passed(&self) -> bool {
+        self.accel_passed.iter().chain(self.gyro_passed.iter()).all(|&passed| passed)
+    }
+}
+
+// Bias measured by `calibrate()`, subtracted from scaled readings
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub struct Calibration {
+    pub accel_bias: [f32; 3],   // g
+    pub gyro_bias: [f32; 3],    // dps
+}
+
+// Gyro bias recorded at several die temperatures. At runtime the bias is linearly interpolated
+// at the current temperature (and held constant outside the recorded range).
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub struct GyroTempCompensation {
+    points: [(f32, [f32; 3]); GyroTempCompensation::MAX_POINTS],
+    len: usize,
+}
+
+#[cfg(feature = "mpu9250")]
+impl GyroTempCompensation {
+    pub const MAX_POINTS: usize = 8;
+
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn points(&self) -> &[(f32, [f32; 3])] {
+        &self.points[..self.len]
+    }
+
+    // Insert a (temperature, bias) point keeping the table sorted by temperature.
+    // A point within 0.5 C of an existing one replaces it. Returns false if the table is full.
+    pub fn add_point(&mut self, temperature: f32, bias: [f32; 3]) -> bool {
+        if let Some(existing) = self.points[..self.len]
+            .iter_mut()
+            .find(|(t, _)| (*t - temperature) < 0.5 && (temperature - *t) < 0.5)
+        {
+            *existing = (temperature, bias);
+            return true;
+        }
+
+        if self.len == Self::MAX_POINTS {
+            return false;
+        }
+
+        let index = self.points[..self.len].iter().take_while(|(t, _)| *t < temperature).count();
+        self.points.copy_within(index..self.len, index + 1);
+        self.points[index] = (temperature, bias);
+        self.len += 1;
+        true
+    }
+
+    pub fn bias_at(&self, temperature: f32) -> [f32; 3] {
+        let points = self.points();
+        match points {
+            [] => [0.0; 3],
+            [(_, bias)] => *bias,
+            _ => {
+                let (first, last) = (points[0], points[points.len() - 1]);
+                if temperature <= first.0 {
+                    return first.1;
+                }
+                if temperature >= last.0 {
+                    return last.1;
+                }
+
+                let upper = points.iter().position(|(t, _)| *t >= temperature).unwrap_or(points.len() - 1);
+                let (t0, b0) = points[upper - 1];
+                let (t1, b1) = points[upper];
+                let ratio = (temperature - t0) / (t1 - t0);
+                core::array::from_fn(|axis| b0[axis] + (b1[axis] - b0[axis]) * ratio)
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub struct RawSample {
+    pub accel: [i16; 3],
+    pub temp: i16,
+    pub gyro: [i16; 3],
+}
+
+#[cfg(feature = "mpu9250")]
+impl RawSample {
+    // State of the FSYNC pin latched into the LSB selected by `location`, None if FSYNC is disabled
+    pub fn fsync_flag(&self, location: FsyncLocation) -> Option<bool> {
+        let word = match location {
+            FsyncLocation::Disabled => return None,
+            FsyncLocation::TempOutL => self.temp,
+            FsyncLocation::GyroXoutL => self.gyro[0],
+            FsyncLocation::GyroYoutL => self.gyro[1],
+            FsyncLocation::GyroZoutL => self.gyro[2],
+            FsyncLocation::AccelXoutL => self.accel[0],
+            FsyncLocation::AccelYoutL => self.accel[1],
+            FsyncLocation::AccelZoutL => self.accel[2],
+        };
+        Some(word & 0x01 != 0)
+    }
+}
+
+// EXT_SYNC_SET: output register whose LSB is replaced by the latched FSYNC pin state
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub enum FsyncLocation {
+    #[default]
+    Disabled = 0x00,
+    TempOutL = 0x01,
+    GyroXoutL = 0x02,
+    GyroYoutL = 0x03,
+    GyroZoutL = 0x04,
+    AccelXoutL = 0x05,
+    AccelYoutL = 0x06,
+    AccelZoutL = 0x07,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub struct Sample {
+    pub acceleration: [f32; 3],       // g
+    pub angular_velocity: [f32; 3],   // dps
+    pub temperature: f32,             // Celsius
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(docsrs, doc(cfg(feature = "mpu9250")))]
+pub struct NineDofSample {
+    pub acceleration: [f32; 3],       // g
+    pub angular_velocity: [f32; 3],   // dps
+    pub magnetic_field: [f32; 3],     // uT
+    pub temperature: f32,             // Celsius
+}
+
+
+#[cfg(feature = "mpu9250")]
+impl<I2C, S> Mpu9250<I2C, S> {
+    // Consume the driver in either typestate and return the I2C bus
+    pub fn release(self) -> I2C {
+        self.i2c
+    }
+
+    pub fn address(&self) -> u8 {
+        self.address
+    }
+}
+
+#[cfg(feature = "mpu9250")]
+impl<I2C, E> Mpu9250<I2C, Uninitialized>
+where
     I2C: I2c<Error = E>
 {
     pub fn new(i2c: I2C, address: u8) -> Self {
@@ -68,24 +632,174 @@ where
             address,
             accel_scale: 0.0,
             gyro_scale: 0.0,
+            accel_range: AccelRange::Range2G,
+            gyro_range: GyroRange::Range250Dps,
+            mag_adjustment: [0.0; 3],
+            calibration: Calibration::default(),
+            mag_calibration: MagCalibration::default(),
+            gyro_temp_compensation: None,
+            fsync_location: FsyncLocation::Disabled,
+            variant: None,
+            wake_on_motion_snapshot: None,
+            _state: PhantomData,
         }
     }
 
-    pub fn verify_identity(&mut self) -> Result<(), Error<E>> {
+    // Verify the chip, wake it, set both ranges and move into the configured state
+    pub fn configure(self, accel_range: AccelRange, gyro_range: GyroRange) -> Result<Mpu9250<I2C, Configured>, Error<E>> {
+        let mut sensor = Mpu9250 {
+            i2c: self.i2c,
+            address: self.address,
+            accel_scale: self.accel_scale,
+            gyro_scale: self.gyro_scale,
+            accel_range: self.accel_range,
+            gyro_range: self.gyro_range,
+            mag_adjustment: self.mag_adjustment,
+            calibration: self.calibration,
+            mag_calibration: self.mag_calibration,
+            gyro_temp_compensation: self.gyro_temp_compensation,
+            fsync_location: self.fsync_location,
+            variant: self.variant,
+            wake_on_motion_snapshot: self.wake_on_motion_snapshot,
+            _state: PhantomData,
+        };
+        sensor.initialize_sensor(accel_range, gyro_range)?;
+        Ok(sensor)
+    }
+}
+
+#[cfg(feature = "mpu9250")]
+impl<I2C, E> Mpu9250<I2C>
+where 
+    I2C: I2c<Error = E>
+{
+    // Size of the documented register map (0x00-0x7E) captured by `dump_registers()`
+    pub const REGISTER_DUMP_SIZE: usize = 0x7F;
+
+    fn read_reg(&mut self, register: u8) -> Result<u8, Error<E>> {
         let mut buffer = [0u8];
-        self.i2c.write_read(self.address, &[WHO_AM_I], &mut buffer)?;
-        if buffer[0] != WHO_AM_I_VALUE {
-            return Err(Error::NotDetected);
-        }
+        self.i2c.write_read(self.address, &[register], &mut buffer).map_err(Error::read(register))?;
+        Ok(buffer[0])
+    }
+
+    fn write_reg(&mut self, register: u8, value: u8) -> Result<(), Error<E>> {
+        self.i2c.write(self.address, &[register, value]).map_err(Error::write(register))?;
+        Ok(())
+    }
+
+    // Read-modify-write: replace the bits selected by `mask` with `value`
+    fn update_reg(&mut self, register: u8, mask: u8, value: u8) -> Result<(), Error<E>> {
+        let current = self.read_reg(register)?;
+        self.write_reg(register, (current & !mask) | (value & mask))
+    }
+
+    // Unchecked register access for settings the driver does not wrap yet. Writes bypass the
+    // driver's cached state (ranges, scale factors, FIFO layout), so changing registers it
+    // manages can make later conversions wrong.
+    #[cfg(feature = "raw-registers")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "raw-registers")))]
+    pub fn read_register(&mut self, register: u8) -> Result<u8, Error<E>> {
+        self.read_reg(register)
+    }
+
+    #[cfg(feature = "raw-registers")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "raw-registers")))]
+    pub fn write_register(&mut self, register: u8, value: u8) -> Result<(), Error<E>> {
+        self.write_reg(register, value)
+    }
+
+    // Read the register, pass its value through `f` and write the result back
+    #[cfg(feature = "raw-registers")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "raw-registers")))]
+    pub fn modify_register<F>(&mut self, register: u8, f: F) -> Result<u8, Error<E>>
+    where
+        F: FnOnce(u8) -> u8,
+    {
+        let value = f(self.read_reg(register)?);
+        self.write_reg(register, value)?;
+        Ok(value)
+    }
+
+    pub fn verify_identity(&mut self) -> Result<(), Error<E>> {
+        self.detect_variant()?;
         Ok(())
     }
 
+    // Identify which member of the MPU9250 family is on the bus. The result is remembered so
+    // magnetometer access fails early on parts without an AK8963.
+    pub fn detect_variant(&mut self) -> Result<ChipVariant, Error<E>> {
+        let who_am_i = self.read_reg(WHO_AM_I)?;
+        let variant = ChipVariant::from_who_am_i(who_am_i).ok_or(Error::NotDetected)?;
+        self.variant = Some(variant);
+        Ok(variant)
+    }
+
+    // Variant found by the last `detect_variant()` or `configure()`, if any
+    pub fn variant(&self) -> Option<ChipVariant> {
+        self.variant
+    }
+
+    fn temperature_from_raw(&self, raw: i16) -> f32 {
+        self.variant.unwrap_or(ChipVariant::Mpu9250).temperature_celsius(raw)
+    }
+
+    // MPU6500/MPU6515 silicon has no AK8963 behind it; fail early instead of timing out on the bus
+    fn ensure_magnetometer(&self) -> Result<(), Error<E>> {
+        match self.variant {
+            Some(variant) if !variant.has_magnetometer() => Err(Error::NotDetected),
+            _ => Ok(()),
+        }
+    }
+
+    pub fn device_info(&mut self) -> Result<DeviceInfo, Error<E>> {
+        let variant = self.detect_variant()?;
+
+        Ok(DeviceInfo {
+            device: variant.device(),
+            part_id: variant.who_am_i(),
+            revision: None,   // No documented revision register
+            address: self.address,
+        })
+    }
+
+    // Wake the device with the auto-selected PLL clock, clearing SLEEP, CYCLE and standby bits
     pub fn configure_power(&mut self) -> Result<(), Error<E>> {
-        let config = 0x01;
-        self.i2c.write(self.address, &[PWR_MGMT_1, config])?;
+        let config = ClockSource::AutoPll as u8;
+        self.i2c.write(self.address, &[PWR_MGMT_1, config]).map_err(Error::write(PWR_MGMT_1))?;
+        Ok(())
+    }
+
+    // Return every register to its power-on default: set H_RESET, wait for the reset to complete
+    // and wake the device again. The chip comes back at 2 g / 250 dps, so the cached scale
+    // factors are updated to match; the FSYNC setting is cleared as well.
+    pub fn reset(&mut self, delay: &mut impl DelayNs) -> Result<(), Error<E>> {
+        self.write_reg(PWR_MGMT_1, 0x80)?;
+        delay.delay_ms(100);
+
+        self.configure_power()?;
+        delay.delay_ms(10);   // Allow the PLL to lock
+
+        self.accel_scale = 2.0 / 32768.0;
+        self.gyro_scale = 250.0 / 32768.0;
+        self.accel_range = AccelRange::Range2G;
+        self.gyro_range = GyroRange::Range250Dps;
+        self.fsync_location = FsyncLocation::Disabled;
+        self.wake_on_motion_snapshot = None;
         Ok(())
     }
 
+    pub fn set_clock_source(&mut self, source: ClockSource) -> Result<(), Error<E>> {
+        self.update_reg(PWR_MGMT_1, 0x07, source as u8)
+    }
+
+    pub fn get_clock_source(&mut self) -> Result<ClockSource, Error<E>> {
+        match self.read_reg(PWR_MGMT_1)? & 0x07 {
+            0x00 | 0x06 => Ok(ClockSource::Internal),
+            0x07 => Ok(ClockSource::Stop),
+            _ => Ok(ClockSource::AutoPll),
+        }
+    }
+
     pub fn setup_accelerometer(&mut self, range: AccelRange) -> Result<(), Error<E>> {
         let (config_value, scale) = match range {
             AccelRange::Range2G => (0x00, 2.0 / 32768.0),
@@ -93,8 +807,9 @@ where
             AccelRange::Range8G => (0x10, 8.0 / 32768.0),
             AccelRange::Range16G => (0x18, 16.0 / 32768.0),
         };
-        self.i2c.write(self.address, &[ACCEL_CONFIG, config_value])?;
+        self.i2c.write(self.address, &[ACCEL_CONFIG, config_value]).map_err(Error::write(ACCEL_CONFIG))?;
         self.accel_scale = scale;
+        self.accel_range = range;
         Ok(())
     }
 
@@ -105,12 +820,34 @@ where
             GyroRange::Range1000Dps => (0x10, 1000.0 / 32768.0),
             GyroRange::Range2000Dps => (0x18, 2000.0 / 32768.0),
         };
-        self.i2c.write(self.address, &[GYRO_CONFIG, config_value])?;
+        self.i2c.write(self.address, &[GYRO_CONFIG, config_value]).map_err(Error::write(GYRO_CONFIG))?;
         self.gyro_scale = scale;
+        self.gyro_range = range;
         Ok(())
     }
 
-    pub fn initialize_sensor(&mut self, accel_range: AccelRange, gyro_range: GyroRange) -> Result<(), Error<E>> {
+    // Range currently held by the chip, read back from ACCEL_CONFIG
+    pub fn get_accel_range(&mut self) -> Result<AccelRange, Error<E>> {
+        match (self.read_reg(ACCEL_CONFIG)? >> 3) & 0x03 {
+            0x00 => Ok(AccelRange::Range2G),
+            0x01 => Ok(AccelRange::Range4G),
+            0x02 => Ok(AccelRange::Range8G),
+            _ => Ok(AccelRange::Range16G),
+        }
+    }
+
+    // Range currently held by the chip, read back from GYRO_CONFIG
+    pub fn get_gyro_range(&mut self) -> Result<GyroRange, Error<E>> {
+        match (self.read_reg(GYRO_CONFIG)? >> 3) & 0x03 {
+            0x00 => Ok(GyroRange::Range250Dps),
+            0x01 => Ok(GyroRange::Range500Dps),
+            0x02 => Ok(GyroRange::Range1000Dps),
+            _ => Ok(GyroRange::Range2000Dps),
+        }
+    }
+
+    // Reached through `configure()` so the typestate transition cannot be bypassed
+    pub(crate) fn initialize_sensor(&mut self, accel_range: AccelRange, gyro_range: GyroRange) -> Result<(), Error<E>> {
         self.verify_identity()?;
         self.configure_power()?;
         self.setup_accelerometer(accel_range)?;
@@ -120,7 +857,7 @@ where
 
     pub fn read_accel_raw(&mut self) -> Result<[i16; 3], Error<E>> {
         let mut buffer = [0u8; 6];
-        self.i2c.write_read(self.address, &[ACCEL_XOUT_H], &mut buffer)?;
+        self.i2c.write_read(self.address, &[ACCEL_XOUT_H], &mut buffer).map_err(Error::read(ACCEL_XOUT_H))?;
         let x = ((buffer[0] as i16) << 8) | buffer[1] as i16;
         let y = ((buffer[2] as i16) << 8) | buffer[3] as i16;
         let z = ((buffer[4] as i16) << 8) | buffer[5] as i16;
@@ -129,7 +866,7 @@ where
 
     pub fn read_gyro_raw(&mut self) -> Result<[i16; 3], Error<E>> {
         let mut buffer = [0u8; 6];
-        self.i2c.write_read(self.address, &[GYRO_XOUT_H], &mut buffer)?;
+        self.i2c.write_read(self.address, &[GYRO_XOUT_H], &mut buffer).map_err(Error::read(GYRO_XOUT_H))?;
         let x = ((buffer[0] as i16) << 8) | buffer[1] as i16;
         let y = ((buffer[2] as i16) << 8) | buffer[3] as i16;
         let z = ((buffer[4] as i16) << 8) | buffer[5] as i16;
@@ -138,62 +875,951 @@ where
 
     pub fn read_temp_raw(&mut self) -> Result<i16, Error<E>> {
         let mut buffer = [0u8; 2];
-        self.i2c.write_read(self.address, &[TEMP_OUT_H], &mut buffer)?;
+        self.i2c.write_read(self.address, &[TEMP_OUT_H], &mut buffer).map_err(Error::read(TEMP_OUT_H))?;
         let temp = ((buffer[0] as i16) << 8) | buffer[1] as i16;
         Ok(temp)
     }
 
     pub fn read_acceleration(&mut self) -> Result<[f32; 3], Error<E>> {
         let raw = self.read_accel_raw()?;
-        let x = raw[0] as f32 * self.accel_scale;
-        let y = raw[1] as f32 * self.accel_scale;
-        let z = raw[2] as f32 * self.accel_scale;
+        let bias = self.calibration.accel_bias;
+        let x = raw[0] as f32 * self.accel_scale - bias[0];
+        let y = raw[1] as f32 * self.accel_scale - bias[1];
+        let z = raw[2] as f32 * self.accel_scale - bias[2];
         Ok([x, y, z])
     }
 
     pub fn read_angular_velocity(&mut self) -> Result<[f32; 3], Error<E>> {
         let raw = self.read_gyro_raw()?;
-        let x = raw[0] as f32 * self.gyro_scale;
-        let y = raw[1] as f32 * self.gyro_scale;
-        let z = raw[2] as f32 * self.gyro_scale;
+        // Temperature compensation costs one extra transaction per read
+        let temperature = match self.gyro_temp_compensation {
+            Some(_) => Some(self.read_temperature_celsius()?),
+            None => None,
+        };
+        let bias = self.gyro_bias_at(temperature);
+        let x = raw[0] as f32 * self.gyro_scale - bias[0];
+        let y = raw[1] as f32 * self.gyro_scale - bias[1];
+        let z = raw[2] as f32 * self.gyro_scale - bias[2];
         Ok([x, y, z])
     }
 
+    // Ranges matching the cached scale factors, Range2G/Range250Dps if the sensor was never configured
+    fn configured_ranges(&self) -> (AccelRange, GyroRange) {
+        (self.accel_range, self.gyro_range)
+    }
+
+    // Integer-only conversions for targets without an FPU. Full scale is 2^k g (or 250 * 2^k dps),
+    // so the scaling reduces to a multiply and a shift. Software calibration is not applied;
+    // use `write_calibration_to_offsets()` to have the chip remove the bias instead.
+    #[cfg_attr(docsrs, doc(cfg(feature = "fixed-point")))]
+    #[cfg(feature = "fixed-point")]
+    pub fn read_acceleration_mg(&mut self) -> Result<[i32; 3], Error<E>> {
+        let raw = self.read_accel_raw()?;
+        let multiplier: i32 = match self.accel_range {
+            AccelRange::Range2G => 1,
+            AccelRange::Range4G => 2,
+            AccelRange::Range8G => 4,
+            AccelRange::Range16G => 8,
+        };
+        // mg = raw * 2000 * multiplier / 32768
+        Ok(raw.map(|value| (value as i32 * multiplier * 125) / 2048))
+    }
+
+    #[cfg_attr(docsrs, doc(cfg(feature = "fixed-point")))]
+    #[cfg(feature = "fixed-point")]
+    pub fn read_angular_velocity_mdps(&mut self) -> Result<[i32; 3], Error<E>> {
+        let raw = self.read_gyro_raw()?;
+        let multiplier: i64 = match self.gyro_range {
+            GyroRange::Range250Dps => 1,
+            GyroRange::Range500Dps => 2,
+            GyroRange::Range1000Dps => 4,
+            GyroRange::Range2000Dps => 8,
+        };
+        // mdps = raw * 250000 * multiplier / 32768, which overflows i32 at 2000 dps
+        Ok(raw.map(|value| ((value as i64 * multiplier * 15625) / 2048) as i32))
+    }
+
+    // Plausibility limits matching the currently configured ranges
+    pub fn validation_limits(&self) -> Limits {
+        Limits::for_imu(self.accel_scale * 32768.0, self.gyro_scale * 32768.0)
+    }
+
     pub fn read_temperature_celsius(&mut self) -> Result<f32, Error<E>> {
         let raw = self.read_temp_raw()?;
-        let temperature = (raw as f32) / 340.0 + 36.53;
-        Ok(temperature)
+        Ok(self.temperature_from_raw(raw))
+    }
+
+    // Enable I2C bypass and configure the AK8963 for 16-bit continuous measurement at 100 Hz.
+    // Must be called before reading the magnetometer.
+    pub fn initialize_magnetometer(&mut self) -> Result<(), Error<E>> {
+        self.ensure_magnetometer()?;
+
+        // Disable the I2C master and route the auxiliary bus to the host. Only I2C_MST_EN and
+        // BYPASS_EN change, FIFO and interrupt pin settings are kept.
+        self.update_reg(USER_CTRL, 0x20, 0x00)?;     // Clear I2C_MST_EN
+        self.update_reg(INT_PIN_CFG, 0x02, 0x02)?;   // Set BYPASS_EN
+
+        let mut wia = [0u8];
+        self.i2c.write_read(AK8963_ADDRESS, &[AK8963_WIA], &mut wia).map_err(Error::read(AK8963_WIA))?;
+        if wia[0] != AK8963_WIA_VALUE {
+            return Err(Error::NotDetected);
+        }
+
+        // Each I2C transaction outlasts the 100us mode transition time, so no explicit delay is needed
+        self.i2c.write(AK8963_ADDRESS, &[AK8963_CNTL1, 0x00]).map_err(Error::write(AK8963_CNTL1))?;   // Power down
+        self.i2c.write(AK8963_ADDRESS, &[AK8963_CNTL1, 0x0F]).map_err(Error::write(AK8963_CNTL1))?;   // Fuse ROM access
+
+        let mut asa = [0u8; 3];
+        self.i2c.write_read(AK8963_ADDRESS, &[AK8963_ASAX], &mut asa).map_err(Error::read(AK8963_ASAX))?;
+        for (adjustment, &value) in self.mag_adjustment.iter_mut().zip(asa.iter()) {
+            // Sensitivity adjustment from the datasheet: Hadj = H * ((ASA - 128) / 256 + 1)
+            *adjustment = ((value as f32 - 128.0) / 256.0 + 1.0) * 0.15;   // 0.15 uT/LSB in 16-bit mode
+        }
+
+        self.i2c.write(AK8963_ADDRESS, &[AK8963_CNTL1, 0x00]).map_err(Error::write(AK8963_CNTL1))?;
+        self.i2c.write(AK8963_ADDRESS, &[AK8963_CNTL1, 0x16]).map_err(Error::write(AK8963_CNTL1))?;   // 16-bit, continuous mode 2 (100 Hz)
+        Ok(())
+    }
+
+    pub fn read_mag_raw(&mut self) -> Result<[i16; 3], Error<E>> {
+        self.ensure_magnetometer()?;
+
+        // HXL..HZH plus ST2; reading ST2 ends the data read and releases the next sample
+        let mut buffer = [0u8; 7];
+        self.i2c.write_read(AK8963_ADDRESS, &[AK8963_HXL], &mut buffer).map_err(Error::read(AK8963_HXL))?;
+
+        // Magnetic sensor overflow
+        if buffer[6] & 0x08 != 0 {
+            return Err(Error::InvalidData);
+        }
+
+        // AK8963 data is little-endian
+        let x = ((buffer[1] as i16) << 8) | buffer[0] as i16;
+        let y = ((buffer[3] as i16) << 8) | buffer[2] as i16;
+        let z = ((buffer[5] as i16) << 8) | buffer[4] as i16;
+        Ok([x, y, z])
+    }
+
+    // Magnetic field in uT with hard/soft-iron correction applied
+    pub fn read_magnetic_field(&mut self) -> Result<[f32; 3], Error<E>> {
+        let field = self.read_magnetic_field_uncalibrated()?;
+        Ok(self.mag_calibration.apply(field))
+    }
+
+    pub fn read_magnetic_field_uncalibrated(&mut self) -> Result<[f32; 3], Error<E>> {
+        let raw = self.read_mag_raw()?;
+        let x = raw[0] as f32 * self.mag_adjustment[0];
+        let y = raw[1] as f32 * self.mag_adjustment[1];
+        let z = raw[2] as f32 * self.mag_adjustment[2];
+        Ok([x, y, z])
+    }
+
+    // Collect `samples` magnetometer readings (10 ms apart) while the user rotates the device
+    // through all orientations, then store and return the resulting hard/soft-iron correction
+    pub fn calibrate_magnetometer(&mut self, samples: u16, delay: &mut impl DelayNs) -> Result<MagCalibration, Error<E>> {
+        let mut calibrator = MagCalibrator::new();
+        for _ in 0..samples {
+            calibrator.add_sample(self.read_magnetic_field_uncalibrated()?);
+            delay.delay_ms(10);
+        }
+
+        let calibration = calibrator
+            .compute()
+            .ok_or(Error::SensorSpecific("Insufficient rotation during magnetometer calibration"))?;
+        self.mag_calibration = calibration;
+        Ok(calibration)
+    }
+
+    pub fn mag_calibration(&self) -> MagCalibration {
+        self.mag_calibration
+    }
+
+    pub fn set_mag_calibration(&mut self, calibration: MagCalibration) {
+        self.mag_calibration = calibration;
+    }
+
+    // Read accel, temperature, gyro and magnetometer in two transactions: one 14-byte burst
+    // from the MPU9250 and one 7-byte burst from the AK8963
+    pub fn read_9dof(&mut self) -> Result<NineDofSample, Error<E>> {
+        let sample = self.read_sample()?;
+        let magnetic_field = self.read_magnetic_field()?;
+
+        Ok(NineDofSample {
+            acceleration: sample.acceleration,
+            angular_velocity: sample.angular_velocity,
+            magnetic_field,
+            temperature: sample.temperature,
+        })
+    }
+
+    // Poll the data-ready flag until a new sample is available and return it, or fail with
+    // `Error::Timeout` after `timeout_us`. Enables the data-ready interrupt source if needed.
+    pub fn wait_for_data_ready(&mut self, delay: &mut impl DelayNs, timeout_us: u32) -> Result<Sample, Error<E>> {
+        const POLL_INTERVAL_US: u32 = 100;
+
+        self.enable_interrupt(InterruptSource::DataReady)?;
+
+        let mut waited_us = 0u32;
+        loop {
+            if self.read_interrupt_status()?.data_ready {
+                return self.read_sample();
+            }
+            if waited_us >= timeout_us {
+                return Err(Error::Timeout);
+            }
+
+            let step = POLL_INTERVAL_US.min(timeout_us - waited_us);
+            delay.delay_us(step);
+            waited_us += step;
+        }
+    }
+
+    // Accel, temperature and gyro registers are contiguous (0x3B-0x48): read them in a single
+    // 14-byte transaction so all channels belong to the same sample
+    pub fn read_all_raw(&mut self) -> Result<RawSample, Error<E>> {
+        let mut buffer = [0u8; IMU_FRAME_SIZE];
+        self.i2c.write_read(self.address, &[ACCEL_XOUT_H], &mut buffer).map_err(Error::read(ACCEL_XOUT_H))?;
+
+        let frame = ImuFrameView::new(&buffer).ok_or(Error::InvalidData)?;
+        Ok(RawSample {
+            accel: frame.accel_raw(),
+            temp: frame.temp_raw(),
+            gyro: frame.gyro_raw(),
+        })
+    }
+
+    pub fn read_sample(&mut self) -> Result<Sample, Error<E>> {
+        let raw = self.read_all_raw()?;
+        let temperature = self.temperature_from_raw(raw.temp);
+        let accel_bias = self.calibration.accel_bias;
+        let gyro_bias = self.gyro_bias_at(Some(temperature));
+
+        Ok(Sample {
+            acceleration: [
+                raw.accel[0] as f32 * self.accel_scale - accel_bias[0],
+                raw.accel[1] as f32 * self.accel_scale - accel_bias[1],
+                raw.accel[2] as f32 * self.accel_scale - accel_bias[2],
+            ],
+            angular_velocity: [
+                raw.gyro[0] as f32 * self.gyro_scale - gyro_bias[0],
+                raw.gyro[1] as f32 * self.gyro_scale - gyro_bias[1],
+                raw.gyro[2] as f32 * self.gyro_scale - gyro_bias[2],
+            ],
+            temperature,
+        })
+    }
+
+    // Put the sensor into wake-on-motion mode: gyro off, accelerometer sampled at `odr`, and the
+    // INT pin asserted when any axis changes by more than `threshold_mg` (4 mg resolution, max 1020 mg).
+    // The power, accel DLPF, interrupt and motion detect registers are saved for `exit_wake_on_motion()`.
+    pub fn enter_wake_on_motion(&mut self, threshold_mg: u16, odr: LowPowerOdr) -> Result<(), Error<E>> {
+        if threshold_mg > 1020 {
+            return Err(Error::ConfigError);
+        }
+
+        // Entering twice must not overwrite the snapshot of the normal configuration
+        if self.wake_on_motion_snapshot.is_none() {
+            self.wake_on_motion_snapshot = Some([
+                self.read_reg(PWR_MGMT_2)?,
+                self.read_reg(ACCEL_CONFIG_2)?,
+                self.read_reg(INT_ENABLE)?,
+                self.read_reg(MOT_DETECT_CTRL)?,
+            ]);
+        }
+
+        // Make sure the accelerometer is running and the gyro is disabled
+        self.update_reg(PWR_MGMT_1, 0x70, 0x00)?;   // Clear CYCLE, SLEEP and GYRO_STANDBY
+        self.write_reg(PWR_MGMT_2, 0x07)?;           // Disable gyro X/Y/Z, keep accel X/Y/Z
+
+        // Accel DLPF at 184 Hz (ACCEL_FCHOICE_B = 0, A_DLPF_CFG = 1)
+        self.update_reg(ACCEL_CONFIG_2, 0x0F, 0x01)?;
+
+        // Add the motion interrupt to whatever the application already enabled
+        self.enable_interrupt(InterruptSource::WakeOnMotion)?;
+
+        // Enable accel hardware intelligence, comparing each sample against the previous one
+        self.write_reg(MOT_DETECT_CTRL, 0xC0)?;
+
+        self.write_reg(WOM_THR, (threshold_mg / 4) as u8)?;
+        self.set_low_power_odr(odr)?;
+
+        // Enter cycle mode
+        self.update_reg(PWR_MGMT_1, 0x20, 0x20)
+    }
+
+    // Leave wake-on-motion mode and restore the configuration saved by `enter_wake_on_motion()`
+    pub fn exit_wake_on_motion(&mut self) -> Result<(), Error<E>> {
+        let [power_2, accel_config_2, int_enable, mot_detect] = self.wake_on_motion_snapshot.ok_or(Error::ConfigError)?;
+
+        self.update_reg(PWR_MGMT_1, 0x20, 0x00)?;
+        self.write_reg(MOT_DETECT_CTRL, mot_detect)?;
+        self.write_reg(INT_ENABLE, int_enable)?;
+        self.write_reg(ACCEL_CONFIG_2, accel_config_2)?;
+        self.write_reg(PWR_MGMT_2, power_2)?;
+        self.wake_on_motion_snapshot = None;
+        Ok(())
+    }
+
+    pub fn is_wake_on_motion_enabled(&self) -> bool {
+        self.wake_on_motion_snapshot.is_some()
+    }
+
+    // Enable the auxiliary I2C master so external sensors on AUX_CL/AUX_DA are sampled by the
+    // MPU9250 itself. This disables I2C bypass, so the AK8963 is no longer directly reachable.
+    pub fn enable_i2c_master(&mut self, clock: I2cMasterClock) -> Result<(), Error<E>> {
+        self.update_reg(INT_PIN_CFG, 0x02, 0x00)?;   // Clear BYPASS_EN
+        self.update_reg(I2C_MST_CTRL, 0x0F, clock as u8)?;
+        self.update_reg(USER_CTRL, 0x20, 0x20)         // Set I2C_MST_EN
+    }
+
+    pub fn disable_i2c_master(&mut self) -> Result<(), Error<E>> {
+        self.update_reg(USER_CTRL, 0x20, 0x00)
+    }
+
+    pub fn configure_aux_slave(&mut self, slave: AuxSlave, config: AuxSlaveConfig) -> Result<(), Error<E>> {
+        if config.address > 0x7F {
+            return Err(Error::ConfigError);
+        }
+
+        let base = I2C_SLV0_ADDR + 3 * slave as u8;
+        let swap = if config.swap_bytes { 0x40 } else { 0x00 };
+
+        let (address, register, ctrl) = match config.transfer {
+            AuxTransfer::Read { register, length } => {
+                if length == 0 || length > 15 {
+                    return Err(Error::ConfigError);
+                }
+                (config.address | 0x80, register, 0x80 | swap | length)
+            }
+            AuxTransfer::Write { register, value } => {
+                self.write_reg(I2C_SLV0_DO + slave as u8, value)?;
+                (config.address, register, 0x80 | 0x01)
+            }
+        };
+
+        // Keep the slave disabled while its address and register are changed
+        self.write_reg(base + 2, 0x00)?;
+        self.write_reg(base, address)?;
+        self.write_reg(base + 1, register)?;
+        self.write_reg(base + 2, ctrl)?;
+
+        // FIFO enable bits: SLV0..2 live in FIFO_EN, SLV3 in I2C_MST_CTRL
+        let fifo_bit = if config.to_fifo { 0xFF } else { 0x00 };
+        match slave {
+            AuxSlave::Slave3 => self.update_reg(I2C_MST_CTRL, 0x20, fifo_bit),
+            _ => self.update_reg(FIFO_EN, 1 << slave as u8, fifo_bit),
+        }
+    }
+
+    pub fn disable_aux_slave(&mut self, slave: AuxSlave) -> Result<(), Error<E>> {
+        self.write_reg(I2C_SLV0_ADDR + 3 * slave as u8 + 2, 0x00)
+    }
+
+    // Copy data gathered by the auxiliary slaves. Reads are stored back to back in slave order,
+    // at most 24 bytes.
+    pub fn read_external_sensor_data(&mut self, buffer: &mut [u8]) -> Result<usize, Error<E>> {
+        let length = buffer.len().min(24);
+        self.i2c.write_read(self.address, &[EXT_SENS_DATA_00], &mut buffer[..length]).map_err(Error::read(EXT_SENS_DATA_00))?;
+        Ok(length)
+    }
+
+    // One-shot register write to an external device through slave 4
+    pub fn aux_write(&mut self, address: u8, register: u8, value: u8) -> Result<(), Error<E>> {
+        self.write_reg(I2C_SLV4_ADDR, address & 0x7F)?;
+        self.write_reg(I2C_SLV4_REG, register)?;
+        self.write_reg(I2C_SLV4_DO, value)?;
+        self.write_reg(I2C_SLV4_CTRL, 0x80)?;
+        self.wait_slave4_done()
+    }
+
+    // One-shot register read from an external device through slave 4
+    pub fn aux_read(&mut self, address: u8, register: u8) -> Result<u8, Error<E>> {
+        self.write_reg(I2C_SLV4_ADDR, 0x80 | (address & 0x7F))?;
+        self.write_reg(I2C_SLV4_REG, register)?;
+        self.write_reg(I2C_SLV4_CTRL, 0x80)?;
+        self.wait_slave4_done()?;
+        self.read_reg(I2C_SLV4_DI)
+    }
+
+    fn wait_slave4_done(&mut self) -> Result<(), Error<E>> {
+        mpu_common::wait_slave4_done(|| self.read_reg(I2C_MST_STATUS))
+    }
+
+    // Disable individual accelerometer/gyroscope axes. Standby axes read as zero.
+    pub fn set_axis_standby(&mut self, standby: AxisStandby) -> Result<(), Error<E>> {
+        self.update_reg(PWR_MGMT_2, 0x3F, standby.to_bits())
+    }
+
+    pub fn get_axis_standby(&mut self) -> Result<AxisStandby, Error<E>> {
+        Ok(AxisStandby::from_bits(self.read_reg(PWR_MGMT_2)?))
+    }
+
+    // Snapshot the register map into `buffer`, indexed by register address. Registers with read
+    // side effects are not read and appear as 0x00: I2C_MST_STATUS (clears the aux master flags),
+    // INT_STATUS (clears interrupts) and FIFO_R_W (pops the FIFO). Returns the number of bytes
+    // written, up to REGISTER_DUMP_SIZE.
+    pub fn dump_registers(&mut self, buffer: &mut [u8]) -> Result<usize, Error<E>> {
+        let length = buffer.len().min(Self::REGISTER_DUMP_SIZE);
+        let skipped = [I2C_MST_STATUS, INT_STATUS, FIFO_R_W];
+
+        let mut start = 0usize;
+        while start < length {
+            if skipped.contains(&(start as u8)) {
+                buffer[start] = 0x00;
+                start += 1;
+                continue;
+            }
+
+            // Burst-read up to the next skipped register
+            let end = skipped
+                .iter()
+                .map(|&register| register as usize)
+                .filter(|&register| register > start)
+                .min()
+                .unwrap_or(length)
+                .min(length);
+            self.i2c.write_read(self.address, &[start as u8], &mut buffer[start..end]).map_err(Error::read(start as u8))?;
+            start = end;
+        }
+
+        Ok(length)
+    }
+
+    // Accel-only low-power cycle mode: the chip sleeps between single accelerometer samples taken
+    // at `odr`. The gyro is disabled; ranges and other configuration are left untouched.
+    pub fn enter_low_power_accel_mode(&mut self, odr: LowPowerOdr) -> Result<(), Error<E>> {
+        self.update_reg(PWR_MGMT_1, 0x50, 0x00)?;   // Clear SLEEP and GYRO_STANDBY
+        self.write_reg(PWR_MGMT_2, 0x07)?;           // Disable gyro X/Y/Z
+        self.update_reg(ACCEL_CONFIG_2, 0x08, 0x08)?;   // ACCEL_FCHOICE_B = 1 as required in cycle mode
+        self.set_low_power_odr(odr)?;
+        self.update_reg(PWR_MGMT_1, 0x20, 0x20)        // Set CYCLE
+    }
+
+    // Change the wake-up frequency, can be called while cycling
+    pub fn set_low_power_odr(&mut self, odr: LowPowerOdr) -> Result<(), Error<E>> {
+        self.write_reg(LP_ACCEL_ODR, odr as u8)
+    }
+
+    pub fn exit_low_power_accel_mode(&mut self) -> Result<(), Error<E>> {
+        self.update_reg(PWR_MGMT_1, 0x20, 0x00)?;
+        self.update_reg(ACCEL_CONFIG_2, 0x08, 0x00)?;
+        self.write_reg(PWR_MGMT_2, 0x00)
+    }
+
+    pub fn is_cycle_mode_enabled(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.read_reg(PWR_MGMT_1)? & 0x20 != 0)
+    }
+
+    // Latch the FSYNC pin (e.g. a camera strobe) into the LSB of the selected output register
+    pub fn set_fsync_location(&mut self, location: FsyncLocation) -> Result<(), Error<E>> {
+        self.update_reg(CONFIG, 0x38, (location as u8) << 3)?;
+        self.fsync_location = location;
+        Ok(())
+    }
+
+    pub fn fsync_location(&self) -> FsyncLocation {
+        self.fsync_location
+    }
+
+    // Use FSYNC as an interrupt source in addition to (or instead of) sample tagging
+    pub fn configure_fsync_interrupt(&mut self, enable: bool, active_low: bool) -> Result<(), Error<E>> {
+        let value = if enable { 0x04 } else { 0x00 } | if active_low { 0x08 } else { 0x00 };
+        self.update_reg(INT_PIN_CFG, 0x0C, value)?;
+        if enable {
+            self.enable_interrupt(InterruptSource::Fsync)
+        } else {
+            self.disable_interrupt(InterruptSource::Fsync)
+        }
+    }
+
+    // Read a coherent sample along with the FSYNC flag from the configured location
+    pub fn read_sample_with_fsync(&mut self) -> Result<(RawSample, Option<bool>), Error<E>> {
+        let raw = self.read_all_raw()?;
+        Ok((raw, raw.fsync_flag(self.fsync_location)))
+    }
+
+    // Configure the electrical behavior of the INT pin. BYPASS_EN and the FSYNC bits are preserved.
+    pub fn configure_interrupt_pin(&mut self, config: InterruptPinConfig) -> Result<(), Error<E>> {
+        let mut value = 0u8;
+        if config.level == InterruptLevel::ActiveLow {
+            value |= 0x80;
+        }
+        if config.drive == InterruptDrive::OpenDrain {
+            value |= 0x40;
+        }
+        if config.latch == InterruptLatch::UntilCleared {
+            value |= 0x20;
+        }
+        if config.clear_on_any_read {
+            value |= 0x10;
+        }
+        self.update_reg(INT_PIN_CFG, 0xF0, value)
+    }
+
+    pub fn enable_interrupt(&mut self, interrupt: InterruptSource) -> Result<(), Error<E>> {
+        let mask = interrupt as u8;
+        self.update_reg(INT_ENABLE, mask, mask)
+    }
+
+    pub fn disable_interrupt(&mut self, interrupt: InterruptSource) -> Result<(), Error<E>> {
+        self.update_reg(INT_ENABLE, interrupt as u8, 0x00)
+    }
+
+    // Raw INT_STATUS contents. Reading the register clears the pending interrupts.
+    pub fn read_interrupt_status_raw(&mut self) -> Result<u8, Error<E>> {
+        self.read_reg(INT_STATUS)
+    }
+
+    // Read and clear the interrupt flags
+    pub fn read_interrupt_status(&mut self) -> Result<InterruptStatus, Error<E>> {
+        Ok(InterruptStatus::from_bits(self.read_interrupt_status_raw()?))
+    }
+
+    pub fn clear_interrupts(&mut self) -> Result<(), Error<E>> {
+        self.read_interrupt_status_raw()?;
+        Ok(())
+    }
+
+    // Run the factory self-test on the accelerometer and gyroscope. The sensor configuration is
+    // restored afterwards. Takes roughly half a second.
+    pub fn run_self_test(&mut self, delay: &mut impl DelayNs) -> Result<SelfTestResult, Error<E>> {
+        const SAMPLES: i32 = 200;
+
+        let mut saved = [0u8; 5];
+        for (value, register) in saved.iter_mut().zip([SMPRT_DIV, CONFIG, GYRO_CONFIG, ACCEL_CONFIG, ACCEL_CONFIG_2]) {
+            *value = self.read_reg(register)?;
+        }
+
+        // 1 kHz sample rate, 92 Hz DLPF, 250 dps and 2 g full scale as mandated by the procedure
+        self.write_reg(SMPRT_DIV, 0x00)?;
+        self.write_reg(CONFIG, 0x02)?;
+        self.write_reg(GYRO_CONFIG, 0x00)?;
+        self.write_reg(ACCEL_CONFIG_2, 0x02)?;
+        self.write_reg(ACCEL_CONFIG, 0x00)?;
+
+        let (accel_normal, gyro_normal) = self.average_raw(SAMPLES, delay)?;
+
+        // Enable self-test on all axes and let the output settle
+        self.write_reg(ACCEL_CONFIG, 0xE0)?;
+        self.write_reg(GYRO_CONFIG, 0xE0)?;
+        delay.delay_ms(20);
+
+        let (accel_test, gyro_test) = self.average_raw(SAMPLES, delay)?;
+
+        self.write_reg(ACCEL_CONFIG, 0x00)?;
+        self.write_reg(GYRO_CONFIG, 0x00)?;
+        delay.delay_ms(20);
+
+        let mut gyro_codes = [0u8; 3];
+        let mut accel_codes = [0u8; 3];
+        self.i2c.write_read(self.address, &[SELF_TEST_X_GYRO], &mut gyro_codes).map_err(Error::read(SELF_TEST_X_GYRO))?;
+        self.i2c.write_read(self.address, &[SELF_TEST_X_ACCEL], &mut accel_codes).map_err(Error::read(SELF_TEST_X_ACCEL))?;
+
+        let mut result = SelfTestResult::default();
+        for axis in 0..3 {
+            let accel_response = (accel_test[axis] - accel_normal[axis]) as f32;
+            let gyro_response = (gyro_test[axis] - gyro_normal[axis]) as f32;
+
+            let accel_trim = Self::factory_trim(accel_codes[axis]);
+            let gyro_trim = Self::factory_trim(gyro_codes[axis]);
+
+            if accel_trim != 0.0 {
+                result.accel_ratio[axis] = accel_response / accel_trim;
+                result.accel_passed[axis] = result.accel_ratio[axis] > 0.5 && result.accel_ratio[axis] < 1.5;
+            } else {
+                // No factory trim: response must be within 225..675 mg (16384 LSB/g)
+                let magnitude = if accel_response < 0.0 { -accel_response } else { accel_response };
+                result.accel_passed[axis] = (3686.0..=11059.0).contains(&magnitude);
+            }
+
+            if gyro_trim != 0.0 {
+                result.gyro_ratio[axis] = gyro_response / gyro_trim;
+                result.gyro_passed[axis] = result.gyro_ratio[axis] > 0.5;
+            } else {
+                // No factory trim: response must be at least 60 dps (131 LSB/dps)
+                let magnitude = if gyro_response < 0.0 { -gyro_response } else { gyro_response };
+                result.gyro_passed[axis] = magnitude >= 60.0 * 131.0;
+            }
+        }
+
+        for (&value, register) in saved.iter().zip([SMPRT_DIV, CONFIG, GYRO_CONFIG, ACCEL_CONFIG, ACCEL_CONFIG_2]) {
+            self.write_reg(register, value)?;
+        }
+
+        Ok(result)
+    }
+
+    // Factory self-test response in LSB at the lowest full scale: 2620 * 1.01^(code - 1)
+    fn factory_trim(code: u8) -> f32 {
+        if code == 0 {
+            return 0.0;
+        }
+
+        let mut trim = 2620.0f32;
+        for _ in 1..code {
+            trim *= 1.01;
+        }
+        trim
+    }
+
+    // Measure accel/gyro bias by averaging `samples` readings while the sensor is stationary and
+    // level (Z axis pointing up, reading +1 g). The result is stored in the driver and subtracted
+    // from every scaled reading; use `write_calibration_to_offsets()` to apply it in hardware instead.
+    pub fn calibrate(&mut self, samples: u16, delay: &mut impl DelayNs) -> Result<Calibration, Error<E>> {
+        if samples == 0 || self.accel_scale == 0.0 || self.gyro_scale == 0.0 {
+            return Err(Error::ConfigError);
+        }
+
+        let (accel_mean, gyro_mean) = self.average_raw(samples as i32, delay)?;
+
+        let mut calibration = Calibration::default();
+        for axis in 0..3 {
+            calibration.accel_bias[axis] = accel_mean[axis] as f32 * self.accel_scale;
+            calibration.gyro_bias[axis] = gyro_mean[axis] as f32 * self.gyro_scale;
+        }
+        calibration.accel_bias[2] -= 1.0;   // Remove gravity
+
+        self.calibration = calibration;
+        Ok(calibration)
+    }
+
+    // Measure the gyro bias at the current die temperature and add it to the temperature
+    // compensation table, enabling compensation. Call at several temperatures (e.g. during warm-up)
+    // while the sensor is stationary.
+    pub fn record_gyro_bias_point(&mut self, samples: u16, delay: &mut impl DelayNs) -> Result<(f32, [f32; 3]), Error<E>> {
+        if samples == 0 || self.gyro_scale == 0.0 {
+            return Err(Error::ConfigError);
+        }
+
+        let temperature_start = self.read_temperature_celsius()?;
+        let (_, gyro_mean) = self.average_raw(samples as i32, delay)?;
+        let temperature_end = self.read_temperature_celsius()?;
+
+        let temperature = (temperature_start + temperature_end) / 2.0;
+        let bias = gyro_mean.map(|mean| mean as f32 * self.gyro_scale);
+
+        let mut compensation = self.gyro_temp_compensation.unwrap_or_default();
+        if !compensation.add_point(temperature, bias) {
+            return Err(Error::SensorSpecific("Gyro temperature compensation table is full"));
+        }
+        self.gyro_temp_compensation = Some(compensation);
+        Ok((temperature, bias))
+    }
+
+    // When set, the interpolated bias replaces the static gyro bias from `calibrate()`
+    pub fn set_gyro_temp_compensation(&mut self, compensation: Option<GyroTempCompensation>) {
+        self.gyro_temp_compensation = compensation;
+    }
+
+    pub fn gyro_temp_compensation(&self) -> Option<GyroTempCompensation> {
+        self.gyro_temp_compensation
+    }
+
+    fn gyro_bias_at(&self, temperature: Option<f32>) -> [f32; 3] {
+        match (&self.gyro_temp_compensation, temperature) {
+            (Some(compensation), Some(temperature)) if !compensation.is_empty() => compensation.bias_at(temperature),
+            _ => self.calibration.gyro_bias,
+        }
+    }
+
+    pub fn calibration(&self) -> Calibration {
+        self.calibration
+    }
+
+    pub fn set_calibration(&mut self, calibration: Calibration) {
+        self.calibration = calibration;
+    }
+
+    pub fn clear_calibration(&mut self) {
+        self.calibration = Calibration::default();
+    }
+
+    // Fold a calibration into the hardware offset registers and stop applying it in software
+    pub fn write_calibration_to_offsets(&mut self, calibration: &Calibration) -> Result<(), Error<E>> {
+        let mut gyro_offsets = self.get_gyro_offsets()?;
+        let mut accel_offsets = self.get_accel_offsets()?;
+
+        for axis in 0..3 {
+            // Gyro offsets are in 32.8 LSB/dps, accel offsets in 0.98 mg steps
+            gyro_offsets[axis] = gyro_offsets[axis].saturating_sub(round_to_i16(calibration.gyro_bias[axis] * 32.8));
+            accel_offsets[axis] = accel_offsets[axis].saturating_sub(round_to_i16(calibration.accel_bias[axis] / 0.00098));
+        }
+
+        // Accel first: an out-of-range offset is rejected before anything has been written
+        self.set_accel_offsets(accel_offsets)?;
+        self.set_gyro_offsets(gyro_offsets)?;
+        self.clear_calibration();
+        Ok(())
+    }
+
+    fn average_raw(&mut self, samples: i32, delay: &mut impl DelayNs) -> Result<([i32; 3], [i32; 3]), Error<E>> {
+        mpu_common::average_raw(samples, delay, || Ok((self.read_accel_raw()?, self.read_gyro_raw()?)))
+    }
+
+    // Gyro offset registers, added to the gyro output in hardware, so a bias is cancelled by its negative.
+    // Units are 1/32.8 dps (the +/-1000 dps scale) regardless of the configured range.
+    pub fn set_gyro_offsets(&mut self, offsets: [i16; 3]) -> Result<(), Error<E>> {
+        let mut buffer = [XG_OFFSET_H, 0, 0, 0, 0, 0, 0];
+        for (axis, offset) in offsets.iter().enumerate() {
+            let bytes = offset.to_be_bytes();
+            buffer[1 + axis * 2] = bytes[0];
+            buffer[2 + axis * 2] = bytes[1];
+        }
+        self.i2c.write(self.address, &buffer).map_err(Error::write(XG_OFFSET_H))?;
+        Ok(())
+    }
+
+    pub fn get_gyro_offsets(&mut self) -> Result<[i16; 3], Error<E>> {
+        let mut buffer = [0u8; 6];
+        self.i2c.write_read(self.address, &[XG_OFFSET_H], &mut buffer).map_err(Error::read(XG_OFFSET_H))?;
+        let x = ((buffer[0] as i16) << 8) | buffer[1] as i16;
+        let y = ((buffer[2] as i16) << 8) | buffer[3] as i16;
+        let z = ((buffer[4] as i16) << 8) | buffer[5] as i16;
+        Ok([x, y, z])
+    }
+
+    // Accelerometer offset registers, added to the accel output in hardware.
+    // Units are 0.98 mg (the +/-16 g scale), 15-bit signed. The chip stores factory trim here at
+    // power-on, so read the current values and adjust them rather than writing absolute numbers.
+    pub fn set_accel_offsets(&mut self, offsets: [i16; 3]) -> Result<(), Error<E>> {
+        // The registers hold 15 bits above the reserved bit, reject values that would lose their top bit
+        if offsets.iter().any(|offset| !(ACCEL_OFFSET_MIN..=ACCEL_OFFSET_MAX).contains(offset)) {
+            return Err(Error::ConfigError);
+        }
+
+        for (offset, register) in offsets.iter().zip([XA_OFFSET_H, YA_OFFSET_H, ZA_OFFSET_H]) {
+            // Bit 0 of the low byte is reserved and must be preserved
+            let mut current = [0u8; 2];
+            self.i2c.write_read(self.address, &[register], &mut current).map_err(Error::read(register))?;
+            let value = ((*offset as u16) << 1) | (current[1] as u16 & 0x01);
+            let bytes = value.to_be_bytes();
+            self.i2c.write(self.address, &[register, bytes[0], bytes[1]]).map_err(Error::write(register))?;
+        }
+        Ok(())
+    }
+
+    pub fn get_accel_offsets(&mut self) -> Result<[i16; 3], Error<E>> {
+        let mut offsets = [0i16; 3];
+        for (offset, register) in offsets.iter_mut().zip([XA_OFFSET_H, YA_OFFSET_H, ZA_OFFSET_H]) {
+            let mut buffer = [0u8; 2];
+            self.i2c.write_read(self.address, &[register], &mut buffer).map_err(Error::read(register))?;
+            // Arithmetic shift drops the reserved bit and keeps the sign
+            *offset = (((buffer[0] as i16) << 8) | buffer[1] as i16) >> 1;
+        }
+        Ok(offsets)
     }
 
     pub fn set_sample_rate(&mut self, divider: u8) -> Result<(), Error<E>> {
-        self.i2c.write(self.address, &[SMPRT_DIV, divider])?;
+        self.i2c.write(self.address, &[SMPRT_DIV, divider]).map_err(Error::write(SMPRT_DIV))?;
         Ok(())
     }
 
+    // Configure divider and DLPFs together for the requested output data rate. Rates from 4 Hz to
+    // 1 kHz use the 1 kHz internal rate with the widest gyro/accel bandwidth below Nyquist;
+    // 8 kHz selects the 250 Hz gyro DLPF at which SMPLRT_DIV is ignored. Anything else is a
+    // ConfigError. Returns the achieved rate, which differs when 1000 / hz is not an integer.
+    pub fn set_output_data_rate(&mut self, hz: u16) -> Result<f32, Error<E>> {
+        if hz == 8000 {
+            self.set_dlpf_config(DlpfConfig::Bandwidth260Hz)?;
+            self.set_accel_dlpf_config(AccelDlpfConfig::Bandwidth1046Hz)?;
+            self.set_sample_rate(0)?;
+            return self.get_output_data_rate();
+        }
+        if !(4..=1000).contains(&hz) {
+            return Err(Error::ConfigError);
+        }
+
+        let divider = ((1000 + hz / 2) / hz - 1) as u8;
+        let (gyro_dlpf, accel_dlpf) = match hz / 2 {
+            184.. => (DlpfConfig::Bandwidth184Hz, AccelDlpfConfig::Bandwidth218Hz),
+            92.. => (DlpfConfig::Bandwidth92Hz, AccelDlpfConfig::Bandwidth99Hz),
+            41.. => (DlpfConfig::Bandwidth41Hz, AccelDlpfConfig::Bandwidth45Hz),
+            20.. => (DlpfConfig::Bandwidth20Hz, AccelDlpfConfig::Bandwidth21Hz),
+            10.. => (DlpfConfig::Bandwidth10Hz, AccelDlpfConfig::Bandwidth10Hz),
+            _ => (DlpfConfig::Bandwidth5Hz, AccelDlpfConfig::Bandwidth5Hz),
+        };
+
+        self.set_dlpf_config(gyro_dlpf)?;
+        self.set_accel_dlpf_config(accel_dlpf)?;
+        self.set_sample_rate(divider)?;
+        self.get_output_data_rate()
+    }
+
+    // Gyro output data rate in Hz as currently configured on the chip. SMPLRT_DIV only applies
+    // when the DLPF runs at the 1 kHz internal rate (DLPF_CFG 1-6, FCHOICE_B cleared).
+    pub fn get_output_data_rate(&mut self) -> Result<f32, Error<E>> {
+        if self.read_reg(GYRO_CONFIG)? & 0x03 != 0 {
+            return Ok(32000.0);
+        }
+
+        match self.read_reg(CONFIG)? & 0x07 {
+            0x00 | 0x07 => Ok(8000.0),
+            _ => Ok(1000.0 / (1.0 + self.get_sample_rate_divider()? as f32)),
+        }
+    }
+
+    pub fn get_sample_rate_divider(&mut self) -> Result<u8, Error<E>> {
+        self.read_reg(SMPRT_DIV)
+    }
+
     pub fn set_dlpf_config(&mut self, config: DlpfConfig) -> Result<(), Error<E>> {
+        // (DLPF_CFG, FCHOICE_B)
+        let (config_value, fchoice_b) = match config {
+            DlpfConfig::Bandwidth260Hz => (0x00, 0x00),
+            DlpfConfig::Bandwidth184Hz => (0x01, 0x00),
+            DlpfConfig::Bandwidth92Hz => (0x02, 0x00),
+            DlpfConfig::Bandwidth41Hz => (0x03, 0x00),
+            DlpfConfig::Bandwidth20Hz => (0x04, 0x00),
+            DlpfConfig::Bandwidth10Hz => (0x05, 0x00),
+            DlpfConfig::Bandwidth5Hz => (0x06, 0x00),
+            DlpfConfig::Bandwidth3600Hz => (0x07, 0x00),
+            DlpfConfig::Bandwidth8800Hz => (0x00, 0x01),
+        };
+        // Preserve FIFO_MODE and EXT_SYNC_SET, and the range bits in GYRO_CONFIG
+        self.update_reg(CONFIG, 0x07, config_value)?;
+        self.update_reg(GYRO_CONFIG, 0x03, fchoice_b)
+    }
+
+    // Gyro DLPF setting read back from CONFIG and the FCHOICE_B bits of GYRO_CONFIG
+    pub fn get_dlpf(&mut self) -> Result<DlpfConfig, Error<E>> {
+        let fchoice_b = self.read_reg(GYRO_CONFIG)? & 0x03;
+        if fchoice_b & 0x01 != 0 {
+            return Ok(DlpfConfig::Bandwidth8800Hz);
+        }
+        if fchoice_b == 0x02 {
+            return Ok(DlpfConfig::Bandwidth3600Hz);
+        }
+
+        match self.read_reg(CONFIG)? & 0x07 {
+            0x00 => Ok(DlpfConfig::Bandwidth260Hz),
+            0x01 => Ok(DlpfConfig::Bandwidth184Hz),
+            0x02 => Ok(DlpfConfig::Bandwidth92Hz),
+            0x03 => Ok(DlpfConfig::Bandwidth41Hz),
+            0x04 => Ok(DlpfConfig::Bandwidth20Hz),
+            0x05 => Ok(DlpfConfig::Bandwidth10Hz),
+            0x06 => Ok(DlpfConfig::Bandwidth5Hz),
+            _ => Ok(DlpfConfig::Bandwidth3600Hz),
+        }
+    }
+
+    pub fn set_accel_dlpf_config(&mut self, config: AccelDlpfConfig) -> Result<(), Error<E>> {
+        // ACCEL_FCHOICE_B (bit 3) | A_DLPF_CFG (bits 2:0)
         let config_value = match config {
-            DlpfConfig::Bandwidth260Hz => 0x00,
-            DlpfConfig::Bandwidth184Hz => 0x01,
+            AccelDlpfConfig::Bandwidth1046Hz => 0x08,
+            AccelDlpfConfig::Bandwidth218Hz => 0x01,
+            AccelDlpfConfig::Bandwidth99Hz => 0x02,
+            AccelDlpfConfig::Bandwidth45Hz => 0x03,
+            AccelDlpfConfig::Bandwidth21Hz => 0x04,
+            AccelDlpfConfig::Bandwidth10Hz => 0x05,
+            AccelDlpfConfig::Bandwidth5Hz => 0x06,
+            AccelDlpfConfig::Bandwidth420Hz => 0x07,
         };
-        self.i2c.write(self.address, &[CONFIG, config_value])?;
-        Ok(())
+        self.update_reg(ACCEL_CONFIG_2, 0x0F, config_value)
+    }
+
+    pub fn get_accel_dlpf(&mut self) -> Result<AccelDlpfConfig, Error<E>> {
+        let config = self.read_reg(ACCEL_CONFIG_2)?;
+        if config & 0x08 != 0 {
+            return Ok(AccelDlpfConfig::Bandwidth1046Hz);
+        }
+
+        match config & 0x07 {
+            0x00 | 0x01 => Ok(AccelDlpfConfig::Bandwidth218Hz),
+            0x02 => Ok(AccelDlpfConfig::Bandwidth99Hz),
+            0x03 => Ok(AccelDlpfConfig::Bandwidth45Hz),
+            0x04 => Ok(AccelDlpfConfig::Bandwidth21Hz),
+            0x05 => Ok(AccelDlpfConfig::Bandwidth10Hz),
+            0x06 => Ok(AccelDlpfConfig::Bandwidth5Hz),
+            _ => Ok(AccelDlpfConfig::Bandwidth420Hz),
+        }
+    }
+
+    // Compare the ranges held by the chip with the ones the cached scale factors were computed
+    // for. A mismatch means the device lost its configuration (e.g. after a brown-out).
+    pub fn verify_configuration(&mut self) -> Result<bool, Error<E>> {
+        let (accel_range, gyro_range) = self.configured_ranges();
+        Ok(self.get_accel_range()? == accel_range && self.get_gyro_range()? == gyro_range)
+    }
+
+    // GYRO_STANDBY (PWR_MGMT_1 bit 4): the gyro drive and PLL keep running but the gyros stop
+    // sampling, so resuming takes well under a millisecond instead of a full PLL restart.
+    // Per-axis standby through `set_axis_standby()` does not keep the drive running.
+    pub fn set_gyro_standby(&mut self, standby: bool) -> Result<(), Error<E>> {
+        self.update_reg(PWR_MGMT_1, 0x10, if standby { 0x10 } else { 0x00 })
+    }
+
+    pub fn is_gyro_standby(&mut self) -> Result<bool, Error<E>> {
+        Ok(self.read_reg(PWR_MGMT_1)? & 0x10 != 0)
     }
 
     pub fn enter_sleep_mode(&mut self) -> Result<(), Error<E>> {
-        let mut buffer = [0u8];
-        self.i2c.write(self.address, &[PWR_MGMT_1])?;
-        self.i2c.read(self.address, &mut buffer)?;
-        let new_config = buffer[0] | 0x40;
-        self.i2c.write(self.address, &[PWR_MGMT_1, new_config])?;
-        Ok(())
+        self.update_reg(PWR_MGMT_1, 0x40, 0x40)   // Set SLEEP (bit 6)
     }
 
     pub fn wake_up(&mut self) -> Result<(), Error<E>> {
-        let mut buffer = [0u8];
-        self.i2c.write(self.address, &[PWR_MGMT_1])?;
-        self.i2c.read(self.address, &mut buffer)?;
-        let new_config = buffer[0] & 0xBF;
-        self.i2c.write(self.address, &[PWR_MGMT_1, new_config])?;
+        self.update_reg(PWR_MGMT_1, 0x40, 0x00)   // Clear SLEEP (bit 6)
+    }
+}
+
+#[cfg(feature = "mpu9250")]
+impl<I2C, E> Imu<E> for Mpu9250<I2C>
+where
+    I2C: I2c<Error = E>
+{
+    type AccelRange = AccelRange;
+    type GyroRange = GyroRange;
+
+    fn read_acceleration(&mut self) -> Result<[f32; 3], Error<E>> {
+        Self::read_acceleration(self)
+    }
+
+    fn read_angular_velocity(&mut self) -> Result<[f32; 3], Error<E>> {
+        Self::read_angular_velocity(self)
+    }
+
+    // The driver never sets TEMP_DIS on the MPU9250
+    fn read_temperature(&mut self) -> Result<Option<f32>, Error<E>> {
+        self.read_temperature_celsius().map(Some)
+    }
+
+    fn set_accel_range(&mut self, range: AccelRange) -> Result<(), Error<E>> {
+        self.setup_accelerometer(range)
+    }
+
+    fn set_gyro_range(&mut self, range: GyroRange) -> Result<(), Error<E>> {
+        self.setup_gyroscope(range)
+    }
+
+    fn accel_range(&self) -> AccelRange {
+        self.configured_ranges().0
+    }
+
+    fn gyro_range(&self) -> GyroRange {
+        self.configured_ranges().1
+    }
+}
+
+#[cfg(feature = "mpu9250")]
+impl<I2C, E> Recover<E> for Mpu9250<I2C>
+where
+    I2C: I2c<Error = E>
+{
+    fn recover(&mut self, action: RecoveryAction, delay: &mut impl DelayNs) -> Result<(), Error<E>> {
+        match action {
+            RecoveryAction::SignalPathReset => {
+                // Reset gyro, accel and temperature signal paths
+                self.i2c.write(self.address, &[SIGNAL_PATH_RESET, 0x07]).map_err(Error::write(SIGNAL_PATH_RESET))?;
+            }
+            RecoveryAction::ForceReset => {
+                self.reset(delay)?;
+            }
+            RecoveryAction::Reinitialize => {
+                // `reset()` forgets the ranges, take them first
+                let (accel_range, gyro_range) = self.configured_ranges();
+                self.reset(delay)?;
+                self.initialize_sensor(accel_range, gyro_range)?;
+            }
+        }
         Ok(())
     }
 }
@@ -0,0 +1,54 @@
+// Helpers shared by the MPU6050 and MPU9250 drivers. Both parts lay out the sensor data,
+// offset and auxiliary I2C master registers the same way, so only the register access differs
+// and is passed in by the driver.
+
+use embedded_hal::delay::DelayNs;
+
+use crate::error::Error;
+
+// Accel offset registers are 15-bit signed
+pub(crate) const ACCEL_OFFSET_MIN: i16 = -16384;
+pub(crate) const ACCEL_OFFSET_MAX: i16 = 16383;
+
+pub(crate) fn round_to_i16(value: f32) -> i16 {
+    let rounded = if value < 0.0 { value - 0.5 } else { value + 0.5 };
+    rounded as i16
+}
+
+// Mean of `samples` raw (accel, gyro) readings taken 1 ms apart
+pub(crate) fn average_raw<E>(
+    samples: i32,
+    delay: &mut impl DelayNs,
+    mut read: impl FnMut() -> Result<([i16; 3], [i16; 3]), Error<E>>,
+) -> Result<([i32; 3], [i32; 3]), Error<E>> {
+    let mut accel_sum = [0i32; 3];
+    let mut gyro_sum = [0i32; 3];
+
+    for _ in 0..samples {
+        let (accel, gyro) = read()?;
+        for axis in 0..3 {
+            accel_sum[axis] += accel[axis] as i32;
+            gyro_sum[axis] += gyro[axis] as i32;
+        }
+        delay.delay_ms(1);
+    }
+
+    Ok((accel_sum.map(|sum| sum / samples), gyro_sum.map(|sum| sum / samples)))
+}
+
+// Poll I2C_MST_STATUS, read through `read_status`, until the slave 4 transfer completes
+pub(crate) fn wait_slave4_done<E>(mut read_status: impl FnMut() -> Result<u8, Error<E>>) -> Result<(), Error<E>> {
+    // The transfer runs at the aux bus clock, a few hundred microseconds at most
+    const MAX_POLLS: u16 = 1000;
+
+    for _ in 0..MAX_POLLS {
+        let status = read_status()?;
+        if status & 0x10 != 0 {
+            return Err(Error::SensorSpecific("Aux I2C slave 4 NACK"));
+        }
+        if status & 0x40 != 0 {
+            return Ok(());
+        }
+    }
+    Err(Error::Timeout)
+}
@@ -0,0 +1,183 @@
+// Duty-cycled MAX30102 measurements.
+//
+// Running the LEDs continuously draws several milliamps, which empties a coin cell within days.
+// Most wearables only need a reading every minute or so, so `PowerScheduler` keeps the sensor
+// in shutdown (under 1 uA) and for each measurement:
+//   1. wakes it and waits for the LEDs and ambient light cancellation to settle
+//   2. drains the FIFO for the measurement window, feeding heart rate and SpO2 estimation,
+//      after a die temperature reading if the SpO2 estimator compensates for temperature
+//   3. shuts it down again and sleeps for the rest of the period
+// Time is kept by counting the delays handed to `DelayNs`; the I2C transfers in between are
+// not counted, so the period runs slightly long on slow buses.
+
+use embedded_hal::delay::DelayNs;
+use embedded_hal::i2c::I2c;
+
+use crate::error::Error;
+use crate::heart_rate::HeartRateDetector;
+use crate::math::Float;
+use crate::max30102::Max30102;
+use crate::spo2::{Spo2Estimator, Spo2Reading};
+
+// Timing of one measurement cycle, chained like `Max30102Config`:
+//   PowerSchedule::default().measure_seconds(15).period_seconds(300)
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PowerSchedule {
+    pub measure_ms: u32,   // Samples collected for each measurement
+    pub period_ms: u32,    // From the start of one measurement to the start of the next
+    pub settle_ms: u32,    // Discarded after wake-up
+}
+
+impl Default for PowerSchedule {
+    fn default() -> Self {
+        PowerSchedule {
+            measure_ms: 10_000,
+            period_ms: 60_000,
+            settle_ms: 1_000,
+        }
+    }
+}
+
+impl PowerSchedule {
+    pub fn measure_seconds(mut self, seconds: u32) -> Self {
+        self.measure_ms = seconds.saturating_mul(1000);
+        self
+    }
+
+    pub fn period_seconds(mut self, seconds: u32) -> Self {
+        self.period_ms = seconds.saturating_mul(1000);
+        self
+    }
+
+    pub fn settle_ms(mut self, ms: u32) -> Self {
+        self.settle_ms = ms;
+        self
+    }
+
+    // Time the sensor spends awake per period
+    pub fn active_ms(&self) -> u32 {
+        self.settle_ms.saturating_add(self.measure_ms)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Measurement {
+    pub bpm: Option<Float>,
+    pub spo2: Option<Spo2Reading>,   // None in HeartRate mode or when the window never filled
+    pub samples: usize,              // Samples collected during the window
+    pub dropped: u32,                // Samples lost to FIFO overflow
+}
+
+// `N` is the SpO2 window in samples and has to fit in one measurement window, e.g. at most
+// 1000 for 10 s at 100 samples/s
+pub struct PowerScheduler<const N: usize> {
+    schedule: PowerSchedule,
+    spo2: Spo2Estimator<N>,
+    last: Option<Measurement>,
+}
+
+impl<const N: usize> PowerScheduler<N> {
+    pub fn new(schedule: PowerSchedule) -> Self {
+        PowerScheduler {
+            schedule,
+            spo2: Spo2Estimator::new(),
+            last: None,
+        }
+    }
+
+    pub fn schedule(&self) -> PowerSchedule {
+        self.schedule
+    }
+
+    pub fn set_schedule(&mut self, schedule: PowerSchedule) {
+        self.schedule = schedule;
+    }
+
+    // SpO2 estimator used for each measurement, e.g. to install calibration coefficients
+    pub fn spo2_estimator(&mut self) -> &mut Spo2Estimator<N> {
+        &mut self.spo2
+    }
+
+    pub fn last_measurement(&self) -> Option<Measurement> {
+        self.last
+    }
+
+    // One measurement: wake, settle, collect, shut down. The sensor is shut down again even if
+    // collecting failed. The sensor must already be configured, e.g. with `initialize_sensor()`.
+    pub fn measure<I2C, E>(&mut self, sensor: &mut Max30102<I2C>, delay: &mut impl DelayNs) -> Result<Measurement, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        let rate = sensor.get_sampling_rate()?.hz() as Float / sensor.get_sample_averaging()?.samples() as Float;
+
+        sensor.wakeup()?;
+        let result = self.collect(sensor, delay, rate);
+        let shutdown = sensor.shutdown();
+
+        let measurement = result?;
+        shutdown?;
+        self.last = Some(measurement);
+        Ok(measurement)
+    }
+
+    // Measure, then sleep until the next period starts
+    pub fn run_cycle<I2C, E>(&mut self, sensor: &mut Max30102<I2C>, delay: &mut impl DelayNs) -> Result<Measurement, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        let measurement = self.measure(sensor, delay)?;
+        delay.delay_ms(self.schedule.period_ms.saturating_sub(self.schedule.active_ms()));
+        Ok(measurement)
+    }
+
+    fn collect<I2C, E>(&mut self, sensor: &mut Max30102<I2C>, delay: &mut impl DelayNs, rate: Float) -> Result<Measurement, Error<E>>
+    where
+        I2C: I2c<Error = E>,
+    {
+        let mut detector = HeartRateDetector::<4>::new(rate);
+        self.spo2.reset();
+
+        delay.delay_ms(self.schedule.settle_ms);
+        if self.spo2.temperature_compensation().is_some() {
+            let celsius = sensor.measure_temperature_blocking(delay)?;
+            self.spo2.update_temperature(celsius);
+        }
+        sensor.clear_fifo()?;
+
+        // Poll when the FIFO is about half full, leaving margin before it overflows
+        let poll_ms = ((Max30102::<I2C>::FIFO_DEPTH / 2) as Float * 1000.0 / rate) as u32;
+        let poll_ms = poll_ms.max(1);
+
+        let mut elapsed = 0;
+        let mut samples = 0;
+        let mut dropped = 0;
+        while elapsed < self.schedule.measure_ms {
+            let step = poll_ms.min(self.schedule.measure_ms - elapsed);
+            delay.delay_ms(step);
+            elapsed += step;
+
+            let mut fifo = sensor.samples();
+            for sample in &mut fifo {
+                let sample = sample?;
+                // IR carries the pulse in SpO2 mode, red is the only channel in HeartRate mode
+                if let Some(value) = sample.ir.or(sample.red) {
+                    detector.update(value);
+                }
+                self.spo2.push(sample);
+                samples += 1;
+            }
+            dropped += fifo.dropped();
+        }
+
+        Ok(Measurement {
+            bpm: detector.bpm(),
+            spo2: self.spo2.estimate(),
+            samples,
+            dropped,
+        })
+    }
+}
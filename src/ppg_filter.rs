@@ -0,0 +1,137 @@
+// Preprocessing stages for raw PPG samples.
+//
+// A raw MAX30102 channel is a large DC level (tissue, ambient light, LED current) with a pulse
+// of well under one percent on top, plus baseline wander from breathing and pressure changes.
+// The stages here turn raw FIFO counts into a zero-centred pulse waveform:
+//   DcTracker - integer exponential average of the DC level, subtracted from each sample
+//   BandPass  - first-order high-pass followed by a two-pole low-pass, 0.5-5 Hz by default
+//   PpgFilter - both chained, the usual input stage for beat detection and SpO2
+// Every stage keeps a few words of state and can be fed one sample at a time.
+
+use crate::math::Float;
+
+const TWO_PI: Float = 2.0 * core::f32::consts::PI as Float;
+
+// Exponential DC tracker in 16.16 fixed point. The time constant is 2^shift samples, e.g.
+// shift 7 follows the baseline over ~1.3 s at 100 samples/s.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct DcTracker {
+    shift: u8,
+    dc: Option<i64>,   // 16.16 fixed point
+}
+
+impl Default for DcTracker {
+    fn default() -> Self {
+        Self::new(7)
+    }
+}
+
+impl DcTracker {
+    pub fn new(shift: u8) -> Self {
+        DcTracker { shift: shift.min(16), dc: None }
+    }
+
+    // Feed one raw sample and return it with the DC level removed. The first sample seeds the
+    // tracker, so the output starts at zero instead of at the full DC level.
+    pub fn update(&mut self, sample: u32) -> i32 {
+        let input = (sample as i64) << 16;
+        let dc = match self.dc {
+            Some(dc) => dc + ((input - dc) >> self.shift),
+            None => input,
+        };
+        self.dc = Some(dc);
+        ((input - dc) >> 16) as i32
+    }
+
+    // Current DC estimate in raw counts
+    pub fn dc(&self) -> u32 {
+        self.dc.map_or(0, |dc| (dc >> 16) as u32)
+    }
+
+    pub fn reset(&mut self) {
+        self.dc = None;
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct BandPass {
+    high_pass_alpha: Float,
+    low_pass_alpha: Float,
+    previous_input: Float,
+    high_pass: Float,
+    low_pass: [Float; 2],
+}
+
+impl BandPass {
+    // Pass band between `low_hz` and `high_hz`; the corners are approximate (first-order RC
+    // sections), which is plenty for a signal whose content is 0.7-3.5 Hz
+    pub fn new(sample_rate: Float, low_hz: Float, high_hz: Float) -> Self {
+        let dt = 1.0 / if sample_rate > 0.0 { sample_rate } else { 1.0 };
+        let rc_high = 1.0 / (TWO_PI * low_hz.max(Float::EPSILON));
+        let rc_low = 1.0 / (TWO_PI * high_hz.max(Float::EPSILON));
+
+        BandPass {
+            high_pass_alpha: rc_high / (rc_high + dt),
+            low_pass_alpha: dt / (rc_low + dt),
+            previous_input: 0.0,
+            high_pass: 0.0,
+            low_pass: [0.0; 2],
+        }
+    }
+
+    // 0.5-5 Hz, covering 30-300 BPM
+    pub fn for_ppg(sample_rate: Float) -> Self {
+        Self::new(sample_rate, 0.5, 5.0)
+    }
+
+    pub fn update(&mut self, input: Float) -> Float {
+        self.high_pass = self.high_pass_alpha * (self.high_pass + input - self.previous_input);
+        self.previous_input = input;
+
+        self.low_pass[0] += (self.high_pass - self.low_pass[0]) * self.low_pass_alpha;
+        self.low_pass[1] += (self.low_pass[0] - self.low_pass[1]) * self.low_pass_alpha;
+        self.low_pass[1]
+    }
+
+    pub fn reset(&mut self) {
+        self.previous_input = 0.0;
+        self.high_pass = 0.0;
+        self.low_pass = [0.0; 2];
+    }
+}
+
+// DC removal followed by band-pass filtering. Running the DC tracker first keeps the large
+// DC level out of the floating point stages, which matters for f32 precision.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct PpgFilter {
+    dc: DcTracker,
+    band_pass: BandPass,
+}
+
+impl PpgFilter {
+    pub fn new(sample_rate: Float) -> Self {
+        Self::from_stages(DcTracker::default(), BandPass::for_ppg(sample_rate))
+    }
+
+    pub fn from_stages(dc: DcTracker, band_pass: BandPass) -> Self {
+        PpgFilter { dc, band_pass }
+    }
+
+    // Feed one raw FIFO value (one channel) and return the cleaned AC waveform
+    pub fn update(&mut self, sample: u32) -> Float {
+        let ac = self.dc.update(sample);
+        self.band_pass.update(ac as Float)
+    }
+
+    pub fn dc(&self) -> u32 {
+        self.dc.dc()
+    }
+
+    pub fn reset(&mut self) {
+        self.dc.reset();
+        self.band_pass.reset();
+    }
+}
@@ -0,0 +1,143 @@
+// Vitals from a MAX30102 without the FIFO plumbing.
+//
+// `PulseOximeter` owns the driver and the processing stages and wires them up the way most
+// applications would by hand:
+//   FIFO -> finger detection -> LED current control -> beat detection + SpO2 window
+// Each `poll()` drains whatever the FIFO holds and reports vitals once the SpO2 window has
+// filled with a finger on the sensor. Taking the finger off clears all stages, so stale beats
+// and samples never mix into the next measurement.
+
+use embedded_hal::i2c::I2c;
+
+use crate::agc::AutoGain;
+use crate::error::Error;
+use crate::heart_rate::HeartRateDetector;
+use crate::math::Float;
+use crate::max30102::{FifoSample, FingerDetector, Max30102};
+use crate::ppg_filter::PpgFilter;
+use crate::signal_quality::SignalQuality;
+use crate::spo2::Spo2Estimator;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Vitals {
+    pub bpm: Option<Float>,    // None until two beats have been seen
+    pub spo2: Option<Float>,   // Percent, None while the reading is not valid
+    pub quality: SignalQuality,
+}
+
+// `N` is the SpO2 window in samples; the default covers four seconds at 100 samples/s
+pub struct PulseOximeter<I2C, const N: usize = 400> {
+    sensor: Max30102<I2C>,
+    finger: FingerDetector,
+    gain: AutoGain,
+    waveform_filter: PpgFilter,
+    detector: HeartRateDetector,
+    spo2: Spo2Estimator<N>,
+    waveform: Float,
+}
+
+impl<I2C, E, const N: usize> PulseOximeter<I2C, N>
+where
+    I2C: I2c<Error = E>,
+{
+    // Wrap a configured sensor, e.g. after `initialize_sensor()`. The sample rate and the LED
+    // current the controller starts from are read back from the chip.
+    pub fn new(mut sensor: Max30102<I2C>) -> Result<Self, Error<E>> {
+        let rate = sensor.get_sampling_rate()?.hz() as Float / sensor.get_sample_averaging()?.samples() as Float;
+        let amplitude = sensor.get_led_pulse_amplitude(2)?;
+
+        Ok(PulseOximeter {
+            sensor,
+            finger: FingerDetector::default(),
+            gain: AutoGain::new(amplitude),
+            waveform_filter: PpgFilter::new(rate),
+            detector: HeartRateDetector::new(rate),
+            spo2: Spo2Estimator::new(),
+            waveform: 0.0,
+        })
+    }
+
+    // Drain the FIFO and return the vitals over the current window. None while no finger is
+    // present or the window is still filling after the finger was placed.
+    pub fn poll(&mut self) -> Result<Option<Vitals>, Error<E>> {
+        let mut batch = [FifoSample::default(); 32];
+        loop {
+            let count = self.sensor.read_fifo_batch(&mut batch)?;
+            for sample in &batch[..count] {
+                self.process(sample)?;
+            }
+            if count < batch.len() {
+                break;
+            }
+        }
+
+        if !self.finger.is_finger_present() {
+            return Ok(None);
+        }
+        let (Some(reading), Some(quality)) = (self.spo2.estimate(), self.spo2.quality()) else {
+            return Ok(None);
+        };
+
+        Ok(Some(Vitals {
+            bpm: self.detector.bpm(),
+            spo2: reading.valid.then_some(reading.spo2),
+            quality,
+        }))
+    }
+
+    pub fn is_finger_present(&self) -> bool {
+        self.finger.is_finger_present()
+    }
+
+    // Latest band-passed IR sample, for drawing the pulse wave
+    pub fn waveform(&self) -> Float {
+        self.waveform
+    }
+
+    pub fn sensor(&mut self) -> &mut Max30102<I2C> {
+        &mut self.sensor
+    }
+
+    // SpO2 estimator, e.g. to install calibration coefficients
+    pub fn spo2_estimator(&mut self) -> &mut Spo2Estimator<N> {
+        &mut self.spo2
+    }
+
+    pub fn release(self) -> Max30102<I2C> {
+        self.sensor
+    }
+
+    fn process(&mut self, sample: &FifoSample) -> Result<(), Error<E>> {
+        let was_present = self.finger.is_finger_present();
+        if !self.finger.update(sample) {
+            if was_present {
+                self.reset_pipeline();
+            }
+            return Ok(());
+        }
+
+        // A current change steps the DC level; drop the window rather than let the step pass
+        // for a pulse
+        if self.gain.update(&mut self.sensor, sample)? {
+            self.reset_pipeline();
+            return Ok(());
+        }
+
+        if let Some(value) = sample.ir.or(sample.red) {
+            self.waveform = self.waveform_filter.update(value);
+            self.detector.update(value);
+        }
+        self.spo2.push(*sample);
+        Ok(())
+    }
+
+    fn reset_pipeline(&mut self) {
+        self.gain.reset();
+        self.waveform_filter.reset();
+        self.detector.reset();
+        self.spo2.reset();
+        self.waveform = 0.0;
+    }
+}
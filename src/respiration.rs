@@ -0,0 +1,114 @@
+// Respiration rate from the PPG baseline.
+//
+// Breathing modulates the PPG in several ways; the strongest on a fingertip is the slow swing
+// of the baseline as intrathoracic pressure changes venous return. `RespirationEstimator` keeps
+// the 0.1-0.5 Hz band of the IR signal (6-30 breaths per minute), which removes both the DC
+// level and the cardiac pulse, and counts one breath per full swing of that band through zero.
+// A breath is only counted once the signal has been below minus and then above plus a fraction
+// of its recent amplitude, so the residue of the pulse riding on the baseline cannot double
+// count.
+//
+// Expect a settling time of one or two breaths after start or reset, and feed the estimator a
+// steady signal: pressing on the sensor shifts the baseline in the same band as breathing.
+
+use crate::heart_rate::IntervalAverage;
+use crate::math::Float;
+use crate::ppg_filter::BandPass;
+
+const LOW_CUTOFF_HZ: Float = 0.1;
+const HIGH_CUTOFF_HZ: Float = 0.5;
+
+// Breath intervals accepted, 40 and 4 breaths per minute
+const MIN_INTERVAL_S: Float = 1.5;
+const MAX_INTERVAL_S: Float = 15.0;
+
+// Share of the recent amplitude the signal has to cross on each side of zero
+const HYSTERESIS: Float = 0.3;
+
+// `K` is the number of breath intervals averaged into the reported rate
+pub struct RespirationEstimator<const K: usize = 4> {
+    sample_rate: Float,
+    band_pass: BandPass,
+    min_interval: u32,   // In samples
+    max_interval: u32,
+    envelope_decay: Float,
+
+    reference: Option<u32>,
+    envelope: Float,
+    armed: bool,   // Signal went below the lower threshold since the last breath
+    index: u32,
+    last_breath: Option<u32>,
+    intervals: IntervalAverage<K>,
+}
+
+impl<const K: usize> RespirationEstimator<K> {
+    // `sample_rate` is the effective rate of the samples fed in, as for `HeartRateDetector`
+    pub fn new(sample_rate: Float) -> Self {
+        let sample_rate = if sample_rate > 0.0 { sample_rate } else { 1.0 };
+
+        RespirationEstimator {
+            sample_rate,
+            band_pass: BandPass::new(sample_rate, LOW_CUTOFF_HZ, HIGH_CUTOFF_HZ),
+            min_interval: (sample_rate * MIN_INTERVAL_S) as u32,
+            max_interval: (sample_rate * MAX_INTERVAL_S) as u32,
+            // Falls to half in about seven seconds, roughly one slow breath
+            envelope_decay: 1.0 - 0.1 / sample_rate,
+            reference: None,
+            envelope: 0.0,
+            armed: false,
+            index: 0,
+            last_breath: None,
+            intervals: IntervalAverage::new(),
+        }
+    }
+
+    pub fn sample_rate(&self) -> Float {
+        self.sample_rate
+    }
+
+    // Feed one raw IR sample (red in HeartRate mode), returns true when it completed a breath
+    pub fn update(&mut self, sample: u32) -> bool {
+        // Filtering the offset from the first sample keeps the DC level out of the filter state,
+        // the high-pass removes what is left of it
+        let reference = *self.reference.get_or_insert(sample);
+        let value = self.band_pass.update(sample as Float - reference as Float);
+
+        let index = self.index;
+        self.index = self.index.wrapping_add(1);
+
+        let magnitude = if value < 0.0 { -value } else { value };
+        self.envelope = (self.envelope * self.envelope_decay).max(magnitude);
+        let threshold = self.envelope * HYSTERESIS;
+
+        if value < -threshold {
+            self.armed = true;
+            return false;
+        }
+        if !self.armed || value <= threshold {
+            return false;
+        }
+        self.armed = false;
+
+        let interval = self.last_breath.map(|last| index.wrapping_sub(last));
+        if interval.is_some_and(|interval| interval < self.min_interval) {
+            return false;
+        }
+        self.last_breath = Some(index);
+
+        match interval.filter(|&interval| interval <= self.max_interval) {
+            Some(interval) => self.intervals.push(interval as Float),
+            // Breathing paused or the signal was lost
+            None => self.intervals.clear(),
+        }
+        true
+    }
+
+    // Average rate over the last K breaths, None until one full breath has been measured
+    pub fn breaths_per_minute(&self) -> Option<Float> {
+        self.intervals.per_minute(self.sample_rate)
+    }
+
+    pub fn reset(&mut self) {
+        *self = Self::new(self.sample_rate);
+    }
+}
@@ -0,0 +1,108 @@
+// Signal quality of a PPG window.
+//
+// SpO2 and heart rate computed from a poor signal look just as plausible as good ones, so
+// applications need a separate indicator to decide what to display. For each channel this
+// module measures the DC level, the pulsatile AC part (RMS after removing a linear trend), the
+// crest factor of that AC part and the baseline drift across the window:
+//   perfusion index - AC/DC in percent; below ~0.05 % the pulse is lost in noise
+//   crest factor    - peak over RMS; a clean pulse wave stays around 2, motion spikes push it up
+//   drift           - baseline change over the window relative to DC; pressing or lifting the
+//                     finger shows up here long before it shows up in the pulse
+
+use crate::math::{self, Float};
+
+const MIN_PERFUSION_PERCENT: Float = 0.05;
+const MAX_PERFUSION_PERCENT: Float = 20.0;
+const MAX_CREST_FACTOR: Float = 4.0;
+const MAX_DRIFT: Float = 0.1;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct ChannelStats {
+    pub dc: Float,
+    pub ac: Float,
+    pub crest_factor: Float,
+    pub drift: Float,   // |baseline change| / DC over the whole window
+}
+
+impl ChannelStats {
+    // Statistics of a window of samples in time order, None with fewer than two samples
+    pub fn from_window(samples: &[u32]) -> Option<Self> {
+        Self::from_ring(samples, 0)
+    }
+
+    // Same for a ring buffer whose oldest sample sits at `head`
+    pub fn from_ring(samples: &[u32], head: usize) -> Option<Self> {
+        let len = samples.len();
+        if len < 2 {
+            return None;
+        }
+
+        let n = len as Float;
+        let mean_index = (n - 1.0) / 2.0;
+        let dc = samples.iter().map(|&value| value as Float).sum::<Float>() / n;
+        let ordered = || (0..len).map(move |index| (index as Float - mean_index, samples[(head + index) % len] as Float));
+
+        let mut covariance = 0.0;
+        let mut variance = 0.0;
+        for (offset, value) in ordered() {
+            covariance += offset * (value - dc);
+            variance += offset * offset;
+        }
+        let slope = covariance / variance;
+
+        let mut power = 0.0;
+        let mut peak: Float = 0.0;
+        for (offset, value) in ordered() {
+            let residual = value - dc - slope * offset;
+            power += residual * residual;
+            peak = peak.max(math::abs(residual));
+        }
+
+        let ac = math::sqrt(power / n);
+        Some(ChannelStats {
+            dc,
+            ac,
+            crest_factor: if ac > 0.0 { peak / ac } else { 0.0 },
+            drift: if dc > 0.0 { math::abs(slope * (n - 1.0)) / dc } else { 0.0 },
+        })
+    }
+
+    // AC/DC in percent
+    pub fn perfusion_index(&self) -> Float {
+        if self.dc > 0.0 { 100.0 * self.ac / self.dc } else { 0.0 }
+    }
+
+    fn motion_suspected(&self) -> bool {
+        self.crest_factor > MAX_CREST_FACTOR
+            || self.drift > MAX_DRIFT
+            || self.perfusion_index() > MAX_PERFUSION_PERCENT
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SignalQuality {
+    pub perfusion_index: Float,           // IR channel (red in HeartRate mode), percent
+    pub red_perfusion_index: Option<Float>,
+    pub motion: bool,                     // Motion artefacts suspected on any channel
+    pub usable: bool,                     // Enough pulse and no motion, results can be trusted
+}
+
+impl SignalQuality {
+    // `primary` is the channel used for heart rate (IR, or red in HeartRate mode), `red` the
+    // second channel of an SpO2 measurement if there is one
+    pub fn assess(primary: &ChannelStats, red: Option<&ChannelStats>) -> Self {
+        let motion = primary.motion_suspected() || red.is_some_and(ChannelStats::motion_suspected);
+        let perfused = primary.perfusion_index() >= MIN_PERFUSION_PERCENT
+            && red.is_none_or(|red| red.perfusion_index() >= MIN_PERFUSION_PERCENT);
+
+        SignalQuality {
+            perfusion_index: primary.perfusion_index(),
+            red_perfusion_index: red.map(ChannelStats::perfusion_index),
+            motion,
+            usable: perfused && !motion,
+        }
+    }
+}
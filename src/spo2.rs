@@ -0,0 +1,317 @@
+// Blood oxygen saturation from MAX30102 red/IR samples.
+//
+// Oxygenated and deoxygenated haemoglobin absorb red and infrared light differently, so the
+// ratio of the pulsatile (AC) to constant (DC) part of each channel tracks SpO2:
+//   R = (AC_red / DC_red) / (AC_ir / DC_ir),   SpO2 = a * R^2 + b * R + c
+// `Spo2Estimator` keeps the last N samples, removes the slow baseline drift from each channel
+// and evaluates R over the whole window. The default coefficients are the ones published by
+// Maxim for their reference design. Module geometry, cover glass and LED wavelengths all shift
+// the curve, so a product should fit its own coefficients against a reference oximeter and
+// install them with `with_coefficients()` or `set_coefficients()`, together with the R range
+// the fit covers.
+//
+// The red LED's peak wavelength moves by roughly 0.1-0.2 nm per degree, and the absorption of
+// deoxygenated haemoglobin is steep around 660 nm, so R drifts with LED temperature. With a
+// `TemperatureCompensation` installed, R is corrected linearly against the latest die
+// temperature passed to `update_temperature()` before the calibration curve is applied.
+
+use crate::math::Float;
+use crate::max30102::FifoSample;
+use crate::signal_quality::{ChannelStats, SignalQuality};
+
+// Calibration polynomial SpO2 = a * R^2 + b * R + c
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Spo2Coefficients {
+    pub a: Float,
+    pub b: Float,
+    pub c: Float,
+}
+
+impl Default for Spo2Coefficients {
+    fn default() -> Self {
+        Self::MAXIM
+    }
+}
+
+impl Spo2Coefficients {
+    // Maxim reference design (MAXREFDES117)
+    pub const MAXIM: Spo2Coefficients = Spo2Coefficients::new(-45.060, 30.354, 94.845);
+
+    pub const fn new(a: Float, b: Float, c: Float) -> Self {
+        Spo2Coefficients { a, b, c }
+    }
+
+    pub fn evaluate(&self, ratio: Float) -> Float {
+        (self.a * ratio + self.b) * ratio + self.c
+    }
+}
+
+// R correction for LED temperature: R' = R - ratio_per_celsius * (T - reference_celsius).
+// The slope depends on the LEDs and has to be measured for a design, e.g. by logging R at
+// stable SpO2 while heating the module.
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct TemperatureCompensation {
+    pub ratio_per_celsius: Float,
+    pub reference_celsius: Float,   // Temperature the calibration coefficients were fitted at
+}
+
+impl TemperatureCompensation {
+    // Reference of 25 °C, override with `reference_celsius()` if calibrated elsewhere
+    pub fn new(ratio_per_celsius: Float) -> Self {
+        TemperatureCompensation { ratio_per_celsius, reference_celsius: 25.0 }
+    }
+
+    pub fn reference_celsius(mut self, celsius: Float) -> Self {
+        self.reference_celsius = celsius;
+        self
+    }
+
+    pub fn apply(&self, ratio: Float, celsius: Float) -> Float {
+        ratio - self.ratio_per_celsius * (celsius - self.reference_celsius)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Spo2Reading {
+    pub spo2: Float,    // Percent, clamped to 0-100
+    pub ratio: Float,   // R, the ratio of ratios, after temperature compensation
+    // False when no finger is present, the pulse is too weak to measure, or R falls outside
+    // the range the calibration polynomial was fitted for
+    pub valid: bool,
+}
+
+// Ratio-of-ratios estimator over a sliding window of N samples. At 100 samples/s, N = 400
+// covers four seconds, enough for several heart beats even at rest.
+pub struct Spo2Estimator<const N: usize> {
+    red: [u32; N],
+    ir: [u32; N],
+    head: usize,
+    len: usize,
+    coefficients: Spo2Coefficients,
+    ratio_range: (Float, Float),
+    compensation: Option<TemperatureCompensation>,
+    temperature: Option<Float>,
+    min_dc: u32,
+}
+
+impl<const N: usize> Default for Spo2Estimator<N> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const N: usize> Spo2Estimator<N> {
+    // DC level below which the IR channel is assumed to see no finger. With the 18-bit, 4096 nA
+    // range used by `initialize_sensor()` one count is about 15.6 pA, so 50000 counts is roughly
+    // 780 nA or a fifth of full scale: above what ambient light leaves after cancellation, below
+    // a finger under the default LED currents. It matches `FingerDetector`'s on threshold; lower
+    // it for smaller LED currents and scale it down 2x per range step above 4096 nA.
+    pub const DEFAULT_MIN_DC: u32 = 50_000;
+
+    // Valid R range of the default calibration, roughly 100 % down to 70 % SpO2
+    pub const DEFAULT_RATIO_RANGE: (Float, Float) = (0.3, 1.1);
+
+    // Pulsatile part needed on each channel before the ratio is trusted, as AC/DC
+    const MIN_PERFUSION: Float = 0.0005;
+
+    pub fn new() -> Self {
+        Self::with_coefficients(Spo2Coefficients::default())
+    }
+
+    pub fn with_coefficients(coefficients: Spo2Coefficients) -> Self {
+        Spo2Estimator {
+            red: [0; N],
+            ir: [0; N],
+            head: 0,
+            len: 0,
+            coefficients,
+            ratio_range: Self::DEFAULT_RATIO_RANGE,
+            compensation: None,
+            temperature: None,
+            min_dc: Self::DEFAULT_MIN_DC,
+        }
+    }
+
+    pub fn set_min_dc(&mut self, min_dc: u32) {
+        self.min_dc = min_dc;
+    }
+
+    pub fn coefficients(&self) -> Spo2Coefficients {
+        self.coefficients
+    }
+
+    // Takes effect on the next `estimate()`, the sample window is kept
+    pub fn set_coefficients(&mut self, coefficients: Spo2Coefficients) {
+        self.coefficients = coefficients;
+    }
+
+    // R values the calibration was fitted over; readings outside it are marked invalid
+    pub fn set_ratio_range(&mut self, min: Float, max: Float) {
+        self.ratio_range = (min.min(max), max.max(min));
+    }
+
+    pub fn ratio_range(&self) -> (Float, Float) {
+        self.ratio_range
+    }
+
+    pub fn set_temperature_compensation(&mut self, compensation: Option<TemperatureCompensation>) {
+        self.compensation = compensation;
+    }
+
+    pub fn temperature_compensation(&self) -> Option<TemperatureCompensation> {
+        self.compensation
+    }
+
+    // Latest die temperature, e.g. from `Max30102::measure_temperature_blocking()`. Readings
+    // are left uncompensated until the first temperature arrives.
+    pub fn update_temperature(&mut self, celsius: f32) {
+        self.temperature = Some(celsius as Float);
+    }
+
+    // Samples without both channels (HeartRate mode) are ignored
+    pub fn push(&mut self, sample: FifoSample) {
+        let (Some(red), Some(ir)) = (sample.red, sample.ir) else {
+            return;
+        };
+        if N == 0 {
+            return;
+        }
+        self.red[self.head] = red;
+        self.ir[self.head] = ir;
+        self.head = (self.head + 1) % N;
+        self.len = (self.len + 1).min(N);
+    }
+
+    pub fn extend(&mut self, samples: &[FifoSample]) {
+        for &sample in samples {
+            self.push(sample);
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        N > 0 && self.len == N
+    }
+
+    pub fn reset(&mut self) {
+        self.head = 0;
+        self.len = 0;
+    }
+
+    // SpO2 over the current window, None until the window has filled up once
+    pub fn estimate(&self) -> Option<Spo2Reading> {
+        if !self.is_full() || N < 2 {
+            return None;
+        }
+
+        let red = ChannelStats::from_ring(&self.red, self.head)?;
+        let ir = ChannelStats::from_ring(&self.ir, self.head)?;
+
+        let finger = ir.dc >= self.min_dc as Float && red.dc > 0.0;
+        let red_perfusion = red.perfusion_index() / 100.0;
+        let ir_perfusion = ir.perfusion_index() / 100.0;
+        if !finger || ir_perfusion <= 0.0 {
+            return Some(Spo2Reading { spo2: 0.0, ratio: 0.0, valid: false });
+        }
+
+        let ratio = red_perfusion / ir_perfusion;
+        let ratio = match (self.compensation, self.temperature) {
+            (Some(compensation), Some(celsius)) => compensation.apply(ratio, celsius),
+            _ => ratio,
+        };
+        let spo2 = self.coefficients.evaluate(ratio).clamp(0.0, 100.0);
+        let valid = red_perfusion >= Self::MIN_PERFUSION
+            && ir_perfusion >= Self::MIN_PERFUSION
+            && (self.ratio_range.0..=self.ratio_range.1).contains(&ratio);
+
+        Some(Spo2Reading { spo2, ratio, valid })
+    }
+
+    // Perfusion and motion assessment of the current window, None until it has filled up
+    pub fn quality(&self) -> Option<SignalQuality> {
+        if !self.is_full() {
+            return None;
+        }
+        let red = ChannelStats::from_ring(&self.red, self.head)?;
+        let ir = ChannelStats::from_ring(&self.ir, self.head)?;
+        Some(SignalQuality::assess(&ir, Some(&red)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::math;
+
+    // Red and IR around the same DC with a square-wave pulse; R is the ratio of the amplitudes
+    fn filled<const N: usize>(red_amplitude: u32, ir_amplitude: u32) -> Spo2Estimator<N> {
+        let mut estimator = Spo2Estimator::<N>::new();
+        for index in 0..N as u32 {
+            let (red, ir) = if index % 2 == 0 {
+                (100_000 + red_amplitude, 100_000 + ir_amplitude)
+            } else {
+                (100_000 - red_amplitude, 100_000 - ir_amplitude)
+            };
+            estimator.push(FifoSample { red: Some(red), ir: Some(ir), green: None, index });
+        }
+        estimator
+    }
+
+    // R tolerance covers micromath's approximate square root (a few percent)
+    const RATIO_TOLERANCE: Float = 0.1;
+
+    fn assert_close(actual: Float, expected: Float, tolerance: Float) {
+        assert!(math::abs(actual - expected) <= tolerance, "{actual} != {expected}");
+    }
+
+    #[test]
+    fn coefficients_evaluate_quadratic() {
+        let maxim = Spo2Coefficients::MAXIM;
+        assert_close(maxim.evaluate(0.0), 94.845, 1e-3);
+        assert_close(maxim.evaluate(0.5), 98.757, 1e-3);
+        assert_close(maxim.evaluate(1.0), 80.139, 1e-3);
+        assert_close(Spo2Coefficients::new(1.0, 2.0, 3.0).evaluate(2.0), 11.0, 1e-6);
+    }
+
+    #[test]
+    fn estimate_needs_full_window() {
+        let mut estimator = Spo2Estimator::<100>::new();
+        estimator.push(FifoSample { red: Some(100_000), ir: Some(100_000), green: None, index: 0 });
+        assert_eq!(estimator.estimate(), None);
+    }
+
+    #[test]
+    fn ratio_inside_range_is_valid() {
+        let reading = filled::<100>(500, 1_000).estimate().unwrap();
+        assert_close(reading.ratio, 0.5, RATIO_TOLERANCE);
+        assert_close(reading.spo2, Spo2Coefficients::MAXIM.evaluate(reading.ratio), 1e-3);
+        assert!(reading.valid);
+    }
+
+    #[test]
+    fn ratio_outside_range_is_invalid() {
+        let mut estimator = filled::<100>(1_500, 1_000);
+        let reading = estimator.estimate().unwrap();
+        assert_close(reading.ratio, 1.5, RATIO_TOLERANCE);
+        assert!(!reading.valid);
+
+        // The same window is accepted once the calibration covers it
+        estimator.set_ratio_range(2.0, 0.3);
+        assert_eq!(estimator.ratio_range(), (0.3, 2.0));
+        assert!(estimator.estimate().unwrap().valid);
+    }
+
+    #[test]
+    fn no_finger_is_invalid() {
+        let mut estimator = filled::<100>(500, 1_000);
+        estimator.set_min_dc(200_000);
+        let reading = estimator.estimate().unwrap();
+        assert_eq!(reading.spo2, 0.0);
+        assert!(!reading.valid);
+    }
+}
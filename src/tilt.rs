@@ -0,0 +1,67 @@
+// Roll and pitch from an accelerometer, optionally smoothed with the gyro.
+//
+// The accelerometer alone gives absolute but noisy angles that are disturbed by any linear
+// acceleration; integrating the gyro is smooth but drifts. `TiltFilter` blends the two with a
+// complementary filter, which is usually all a balancing robot or inclinometer needs.
+
+use crate::math::{self, Float};
+
+const RAD_TO_DEG: Float = 57.295_78;
+
+// Roll (rotation about X) and pitch (rotation about Y) in degrees, from acceleration in any unit
+pub fn accel_roll_pitch(accel: [f32; 3]) -> (Float, Float) {
+    let (x, y, z) = (accel[0] as Float, accel[1] as Float, accel[2] as Float);
+    let roll = math::atan2(y, z) * RAD_TO_DEG;
+    let pitch = math::atan2(-x, math::sqrt(y * y + z * z)) * RAD_TO_DEG;
+    (roll, pitch)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct TiltFilter {
+    gyro_weight: Float,
+    roll: Float,
+    pitch: Float,
+    initialized: bool,
+}
+
+impl TiltFilter {
+    // `gyro_weight` in 0.0..=1.0 is the share given to the integrated gyro at each update;
+    // 0.98 at 100 Hz is a common starting point
+    pub fn new(gyro_weight: Float) -> Self {
+        TiltFilter {
+            gyro_weight: gyro_weight.clamp(0.0, 1.0),
+            roll: 0.0,
+            pitch: 0.0,
+            initialized: false,
+        }
+    }
+
+    // Feed one accel (any unit) and gyro (dps) reading taken `dt` seconds after the previous one.
+    // The first update snaps to the accelerometer angles. Returns (roll, pitch) in degrees.
+    pub fn update(&mut self, accel: [f32; 3], gyro: [f32; 3], dt: Float) -> (Float, Float) {
+        let (accel_roll, accel_pitch) = accel_roll_pitch(accel);
+
+        if !self.initialized {
+            self.roll = accel_roll;
+            self.pitch = accel_pitch;
+            self.initialized = true;
+            return (self.roll, self.pitch);
+        }
+
+        let weight = self.gyro_weight;
+        self.roll = weight * (self.roll + gyro[0] as Float * dt) + (1.0 - weight) * accel_roll;
+        self.pitch = weight * (self.pitch + gyro[1] as Float * dt) + (1.0 - weight) * accel_pitch;
+        (self.roll, self.pitch)
+    }
+
+    pub fn angles(&self) -> (Float, Float) {
+        (self.roll, self.pitch)
+    }
+
+    pub fn reset(&mut self) {
+        self.initialized = false;
+        self.roll = 0.0;
+        self.pitch = 0.0;
+    }
+}
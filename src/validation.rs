@@ -0,0 +1,106 @@
+// Plausibility checks for sensor readings.
+//
+// Corrupted I2C transfers usually show up as values outside the physical or configured range
+// (all-ones words, NaN after scaling, impossible temperatures). These checks are opt-in: pass a
+// reading through them before it reaches fusion or logging code and get `Error::InvalidData`
+// instead of a silently poisoned estimate.
+
+use crate::error::Error;
+
+#[cfg(feature = "max30102")]
+use crate::max30102::FifoSample;
+
+// Die temperature operating range shared by the supported sensors
+pub const TEMPERATURE_MIN_C: f32 = -40.0;
+pub const TEMPERATURE_MAX_C: f32 = 85.0;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Limits {
+    pub accel_full_scale_g: f32,
+    pub gyro_full_scale_dps: f32,
+    pub max_accel_magnitude_g: f32,
+    pub temperature_min_c: f32,
+    pub temperature_max_c: f32,
+}
+
+impl Limits {
+    // Limits matching the configured IMU full-scale ranges. The magnitude bound allows every
+    // axis to sit at full scale at once, which is the most a correctly working chip can report.
+    pub fn for_imu(accel_full_scale_g: f32, gyro_full_scale_dps: f32) -> Self {
+        Limits {
+            accel_full_scale_g,
+            gyro_full_scale_dps,
+            max_accel_magnitude_g: accel_full_scale_g * 1.7321,   // sqrt(3)
+            temperature_min_c: TEMPERATURE_MIN_C,
+            temperature_max_c: TEMPERATURE_MAX_C,
+        }
+    }
+
+    pub fn check_acceleration<E>(&self, accel: [f32; 3]) -> Result<[f32; 3], Error<E>> {
+        check_axes(accel, self.accel_full_scale_g)?;
+
+        let magnitude_sq = accel[0] * accel[0] + accel[1] * accel[1] + accel[2] * accel[2];
+        if magnitude_sq > self.max_accel_magnitude_g * self.max_accel_magnitude_g {
+            return Err(Error::InvalidData);
+        }
+        Ok(accel)
+    }
+
+    pub fn check_angular_velocity<E>(&self, gyro: [f32; 3]) -> Result<[f32; 3], Error<E>> {
+        check_axes(gyro, self.gyro_full_scale_dps)?;
+        Ok(gyro)
+    }
+
+    pub fn check_temperature<E>(&self, celsius: f32) -> Result<f32, Error<E>> {
+        if !celsius.is_finite() || celsius < self.temperature_min_c || celsius > self.temperature_max_c {
+            return Err(Error::InvalidData);
+        }
+        Ok(celsius)
+    }
+}
+
+fn check_axes<E>(values: [f32; 3], full_scale: f32) -> Result<(), Error<E>> {
+    // Scaled readings can legitimately reach full scale exactly, allow a small margin for rounding
+    let bound = full_scale * 1.001;
+    for value in values {
+        if !value.is_finite() || value > bound || value < -bound {
+            return Err(Error::InvalidData);
+        }
+    }
+    Ok(())
+}
+
+// True if any axis is pinned at the ADC rail, meaning the reading is clipped
+pub fn is_saturated(raw: [i16; 3]) -> bool {
+    raw.iter().any(|&value| value == i16::MAX || value == i16::MIN)
+}
+
+// MAX30102 samples are left-justified in 18 bits; at lower resolutions the unused LSBs read as zero
+#[cfg(feature = "max30102")]
+fn unused_bits_mask(resolution_bits: u8) -> u32 {
+    (1u32 << (18 - resolution_bits)) - 1
+}
+
+// True if any PPG channel present in the sample is pinned at the top of the ADC range
+#[cfg(feature = "max30102")]
+pub fn is_ppg_saturated(sample: &FifoSample, resolution_bits: u8) -> bool {
+    let unused = unused_bits_mask(resolution_bits.clamp(15, 18));
+    [sample.red, sample.ir, sample.green].into_iter().flatten().any(|value| (value | unused) >= 0x03FFFF)
+}
+
+// Reject PPG samples that are saturated or carry bits the configured resolution cannot produce
+#[cfg(feature = "max30102")]
+pub fn check_ppg_sample<E>(sample: FifoSample, resolution_bits: u8) -> Result<FifoSample, Error<E>> {
+    if !(15..=18).contains(&resolution_bits) {
+        return Err(Error::ConfigError);
+    }
+
+    let invalid = !0x03FFFF | unused_bits_mask(resolution_bits);
+    let stray_bits = [sample.red, sample.ir, sample.green].into_iter().flatten().any(|value| value & invalid != 0);
+    if stray_bits || is_ppg_saturated(&sample, resolution_bits) {
+        return Err(Error::InvalidData);
+    }
+    Ok(sample)
+}
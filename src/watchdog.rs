@@ -0,0 +1,105 @@
+// Stuck-sensor detection with escalating recovery.
+//
+// A sensor on marginal wiring can stop updating while still ACKing on the bus: every read
+// returns the same bytes, or the FIFO write pointer stops moving. `StuckWatchdog` counts
+// consecutive bit-identical observations and, once the threshold is hit, asks the driver to
+// run the next step of a configurable recovery sequence.
+
+use embedded_hal::delay::DelayNs;
+
+use crate::error::Error;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum RecoveryAction {
+    SignalPathReset,   // Reset the analog/digital signal paths or FIFO, keep configuration
+    ForceReset,        // Device reset, chip returns to power-on defaults
+    Reinitialize,      // Device reset followed by the driver's full initialization
+}
+
+// Implemented by drivers that know how to perform each recovery step. The delay covers the
+// settle time after a device reset, before the configuration can be written again.
+pub trait Recover<E> {
+    fn recover(&mut self, action: RecoveryAction, delay: &mut impl DelayNs) -> Result<(), Error<E>>;
+}
+
+// Reported whenever the watchdog triggered a recovery step
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct StuckEvent {
+    pub repeats: u16,
+    pub action: RecoveryAction,
+}
+
+pub struct StuckWatchdog<T, const L: usize = 3> {
+    last: Option<T>,
+    repeats: u16,
+    threshold: u16,
+    sequence: [RecoveryAction; L],
+    level: usize,
+}
+
+impl<T: PartialEq + Copy> StuckWatchdog<T> {
+    // Escalates signal-path reset -> force reset -> full reinit while the sensor stays stuck
+    pub fn new(threshold: u16) -> Self {
+        Self::with_sequence(
+            threshold,
+            [RecoveryAction::SignalPathReset, RecoveryAction::ForceReset, RecoveryAction::Reinitialize],
+        )
+    }
+}
+
+impl<T: PartialEq + Copy, const L: usize> StuckWatchdog<T, L> {
+    pub fn with_sequence(threshold: u16, sequence: [RecoveryAction; L]) -> Self {
+        StuckWatchdog {
+            last: None,
+            repeats: 0,
+            threshold: threshold.max(2),
+            sequence,
+            level: 0,
+        }
+    }
+
+    pub fn reset(&mut self) {
+        self.last = None;
+        self.repeats = 0;
+        self.level = 0;
+    }
+
+    // Feed one observation (a raw reading, a FIFO write pointer, ...). Returns the recovery
+    // action to perform when the value has been identical `threshold` times in a row.
+    // The last step of the sequence is repeated if the sensor stays stuck.
+    pub fn observe(&mut self, value: T) -> Option<RecoveryAction> {
+        if self.last == Some(value) {
+            self.repeats = self.repeats.saturating_add(1);
+        } else {
+            self.last = Some(value);
+            self.repeats = 1;
+            self.level = 0;
+        }
+
+        if self.repeats < self.threshold || L == 0 {
+            return None;
+        }
+
+        let action = self.sequence[self.level.min(L - 1)];
+        self.level = (self.level + 1).min(L - 1);
+        self.repeats = 0;
+        Some(action)
+    }
+
+    // Observe a value and, if the sensor is stuck, run the recovery step on it
+    pub fn check<S, E>(&mut self, sensor: &mut S, value: T, delay: &mut impl DelayNs) -> Result<Option<StuckEvent>, Error<E>>
+    where
+        S: Recover<E>,
+    {
+        let repeats = self.repeats.saturating_add(1);
+        match self.observe(value) {
+            Some(action) => {
+                sensor.recover(action, delay)?;
+                Ok(Some(StuckEvent { repeats, action }))
+            }
+            None => Ok(None),
+        }
+    }
+}